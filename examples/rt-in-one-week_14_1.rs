@@ -6,9 +6,9 @@ fn main() {
     let mut world = HittableList::new();
 
     let ground_material = Lambertian::new(Color::new(0.5, 0.5, 0.5));
-    world.push(Box::new(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
+    world.push(Box::new(Plane::new(
+        Point3::ZERO,
+        Vec3::Y,
         Arc::new(ground_material),
     )));
 
@@ -62,7 +62,7 @@ fn main() {
     )));
 
     match render(
-        &world,
+        &Bvh::new(world, SplitMethod::Sah),
         "output/rt-in-one-week-14.1.png",
         &CameraConfig {
             vfov: 20.0,
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+use crate::{point::Point3, ray::Ray, vec::Vec3};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Aabb {
+    pub const EMPTY: Aabb = Aabb {
+        min: Point3::INFINITY,
+        max: Point3::NEG_INFINITY,
+    };
+
+    // Bounds of primitives such as infinite planes.
+    pub const UNBOUNDED: Aabb = Aabb {
+        min: Point3::NEG_INFINITY,
+        max: Point3::INFINITY,
+    };
+
+    pub fn new(a: Point3, b: Point3) -> Aabb {
+        Aabb {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Point3>) -> Aabb {
+        points
+            .into_iter()
+            .fold(Aabb::EMPTY, |acc, point| acc.union_point(point))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        !self.is_empty() && !(self.min.is_finite() && self.max.is_finite())
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn union_point(&self, point: Point3) -> Aabb {
+        Aabb {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    pub fn expand(&self, delta: f32) -> Aabb {
+        if self.is_empty() {
+            return *self;
+        }
+        let delta = Vec3::splat(delta);
+        Aabb {
+            min: self.min - delta,
+            max: self.max + delta,
+        }
+    }
+
+    pub fn contains(&self, point: Point3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    pub fn diagonal(&self) -> Vec3 {
+        if self.is_empty() {
+            Vec3::ZERO
+        } else {
+            self.max - self.min
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn bounding_sphere(&self) -> (Point3, f32) {
+        let center = self.centroid();
+        let radius = 0.5 * self.diagonal().length();
+        (center, radius)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = self.diagonal();
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let d = self.diagonal();
+        if d.x > d.y && d.x > d.z {
+            0
+        } else if d.y > d.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> bool {
+        let inv_direction = ray.direction.recip();
+        let t0 = (self.min - ray.origin) * inv_direction;
+        let t1 = (self.max - ray.origin) * inv_direction;
+
+        let t_enter = t0.min(t1).max_element().max(ray_t.start);
+        let t_exit = t0.max(t1).min_element().min(ray_t.end);
+
+        t_enter <= t_exit
+    }
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Aabb::EMPTY
+    }
+}
@@ -0,0 +1,183 @@
+pub mod environment_map;
+
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    vec::{Vec2, Vec3},
+};
+
+pub trait Background: Send + Sync + std::fmt::Debug {
+    fn color(&self, ray: &Ray) -> Color<Linear>;
+
+    // Backgrounds that can be importance sampled return a direction towards them.
+    fn sample(&self) -> Option<BackgroundSample> {
+        None
+    }
+
+    fn pdf(&self, _direction: Vec3) -> f32 {
+        0.0
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BackgroundSample {
+    pub direction: Vec3,
+    pub color: Color<Linear>,
+    pub pdf: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Black;
+
+impl Background for Black {
+    fn color(&self, _ray: &Ray) -> Color<Linear> {
+        Color::from(Vec3::ZERO)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Solid {
+    color: Color<Linear>,
+}
+
+impl Solid {
+    pub fn new(color: Color<Linear>) -> Self {
+        Self { color }
+    }
+}
+
+impl Background for Solid {
+    fn color(&self, _ray: &Ray) -> Color<Linear> {
+        self.color
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Gradient {
+    bottom: Color<Linear>,
+    top: Color<Linear>,
+}
+
+impl Gradient {
+    pub fn new(bottom: Color<Linear>, top: Color<Linear>) -> Self {
+        Self { bottom, top }
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Self::new(Color::new(1.0, 1.0, 1.0), Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Background for Gradient {
+    fn color(&self, ray: &Ray) -> Color<Linear> {
+        let unit_direction = ray.direction().normalize();
+        let a = 0.5 * (unit_direction.y + 1.0);
+        Color::from(Vec3::lerp(self.bottom.v, self.top.v, a))
+    }
+}
+
+// Preetham, Shirley and Smits, "A Practical Analytic Model for Daylight" (1999).
+#[derive(Clone, Copy, Debug)]
+pub struct PhysicalSky {
+    sun_direction: Vec3,
+    intensity: f32,
+    perez_luminance: [f32; 5],
+    perez_x: [f32; 5],
+    perez_y: [f32; 5],
+    zenith: Vec2,
+    normalization: Vec3,
+}
+
+impl PhysicalSky {
+    pub fn new(sun_direction: Vec3, turbidity: f32) -> Self {
+        let sun_direction = sun_direction.normalize();
+        let t = turbidity.max(1.0);
+        let theta_s = sun_direction.y.clamp(0.0, 1.0).acos();
+
+        let perez_luminance = [
+            0.1787 * t - 1.4630,
+            -0.3554 * t + 0.4275,
+            -0.0227 * t + 5.3251,
+            0.1206 * t - 2.5771,
+            -0.0670 * t + 0.3703,
+        ];
+        let perez_x = [
+            -0.0193 * t - 0.2592,
+            -0.0665 * t + 0.0008,
+            -0.0004 * t + 0.2125,
+            -0.0641 * t - 0.8989,
+            -0.0033 * t + 0.0452,
+        ];
+        let perez_y = [
+            -0.0167 * t - 0.2608,
+            -0.0950 * t + 0.0092,
+            -0.0079 * t + 0.2102,
+            -0.0441 * t - 1.6537,
+            -0.0109 * t + 0.0529,
+        ];
+
+        let theta = Vec3::new(theta_s.powi(3), theta_s.powi(2), theta_s);
+        let zenith_x = t * t * theta.dot(Vec3::new(0.00166, -0.00375, 0.00209))
+            + t * (theta.dot(Vec3::new(-0.02903, 0.06377, -0.03202)) + 0.00394)
+            + theta.dot(Vec3::new(0.11693, -0.21196, 0.06052))
+            + 0.25886;
+        let zenith_y = t * t * theta.dot(Vec3::new(0.00275, -0.00610, 0.00317))
+            + t * (theta.dot(Vec3::new(-0.04214, 0.08970, -0.04153)) + 0.00516)
+            + theta.dot(Vec3::new(0.15346, -0.26756, 0.06670))
+            + 0.26688;
+
+        let zenith = Vec2::new(zenith_x, zenith_y);
+        let normalization = Vec3::new(
+            perez(&perez_x, 1.0, theta_s.cos()),
+            perez(&perez_y, 1.0, theta_s.cos()),
+            perez(&perez_luminance, 1.0, theta_s.cos()),
+        );
+
+        Self {
+            sun_direction,
+            intensity: 0.25,
+            perez_luminance,
+            perez_x,
+            perez_y,
+            zenith,
+            normalization,
+        }
+    }
+
+    pub fn with_intensity(self, intensity: f32) -> Self {
+        Self { intensity, ..self }
+    }
+}
+
+// Perez et al. luminance distribution, in terms of cos(theta) and cos(gamma).
+fn perez(coefficients: &[f32; 5], cos_theta: f32, cos_gamma: f32) -> f32 {
+    let [a, b, c, d, e] = *coefficients;
+    let gamma = cos_gamma.clamp(-1.0, 1.0).acos();
+    (1.0 + a * (b / cos_theta).exp()) * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+}
+
+impl Background for PhysicalSky {
+    fn color(&self, ray: &Ray) -> Color<Linear> {
+        let direction = ray.direction().normalize();
+        // The model is undefined below the horizon, so mirror the horizon color there.
+        let cos_theta = direction.y.max(0.01);
+        let cos_gamma = direction.dot(self.sun_direction);
+
+        let x = self.zenith.x * perez(&self.perez_x, cos_theta, cos_gamma) / self.normalization.x;
+        let y = self.zenith.y * perez(&self.perez_y, cos_theta, cos_gamma) / self.normalization.y;
+        // Luminance relative to the zenith, which `intensity` maps to.
+        let luminance = perez(&self.perez_luminance, cos_theta, cos_gamma) / self.normalization.z
+            * self.intensity;
+
+        let xyz = Vec3::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+        let rgb = Vec3::new(
+            Vec3::new(3.2406, -1.5372, -0.4986).dot(xyz),
+            Vec3::new(-0.9689, 1.8758, 0.0415).dot(xyz),
+            Vec3::new(0.0557, -0.2040, 1.0570).dot(xyz),
+        );
+
+        Color::from(rgb.max(Vec3::ZERO))
+    }
+}
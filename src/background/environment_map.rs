@@ -0,0 +1,134 @@
+use std::{
+    f32::consts::{PI, TAU},
+    path::Path,
+};
+
+use image::{ImageResult, Rgb32FImage};
+
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    sampling::Distribution2D,
+    utils::Random,
+    vec::{Vec2, Vec3},
+};
+
+use super::{Background, BackgroundSample};
+
+// Equirectangular (latitude-longitude) map; +Y is up and the image center looks down -Z.
+#[derive(Clone, Debug)]
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec3>,
+    intensity: f32,
+    rotation: f32,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    pub fn load(path: impl AsRef<Path>) -> ImageResult<Self> {
+        Ok(Self::new(image::open(path)?.into_rgb32f()))
+    }
+
+    pub fn new(image: Rgb32FImage) -> Self {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels: Vec<Vec3> = image
+            .pixels()
+            .map(|pixel| Vec3::from(pixel.0).max(Vec3::ZERO))
+            .collect();
+
+        // Weight by sin(theta) to undo the stretching of rows near the poles.
+        let weights: Vec<f32> = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, pixel)| {
+                let theta = PI * ((i / width) as f32 + 0.5) / height as f32;
+                luminance(*pixel) * theta.sin()
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            pixels,
+            intensity: 1.0,
+            rotation: 0.0,
+            distribution: Distribution2D::new(&weights, width, height),
+        }
+    }
+
+    pub fn with_intensity(self, intensity: f32) -> Self {
+        Self { intensity, ..self }
+    }
+
+    // Rotates the map around the vertical axis, in degrees.
+    pub fn with_rotation(self, degrees: f32) -> Self {
+        Self {
+            rotation: degrees.to_radians(),
+            ..self
+        }
+    }
+
+    fn direction_to_uv(&self, direction: Vec3) -> Vec2 {
+        let direction = direction.normalize();
+        let phi = direction.x.atan2(-direction.z) - self.rotation;
+        let theta = direction.y.clamp(-1.0, 1.0).acos();
+        Vec2::new((0.5 + phi / TAU).rem_euclid(1.0), theta / PI)
+    }
+
+    fn uv_to_direction(&self, uv: Vec2) -> Vec3 {
+        let phi = (uv.x - 0.5) * TAU + self.rotation;
+        let theta = uv.y * PI;
+        Vec3::new(
+            theta.sin() * phi.sin(),
+            theta.cos(),
+            -theta.sin() * phi.cos(),
+        )
+    }
+
+    fn lookup(&self, uv: Vec2) -> Color<Linear> {
+        let x = ((uv.x * self.width as f32) as usize).min(self.width - 1);
+        let y = ((uv.y * self.height as f32) as usize).min(self.height - 1);
+        Color::from(self.pixels[y * self.width + x] * self.intensity)
+    }
+}
+
+fn luminance(color: Vec3) -> f32 {
+    color.dot(Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+impl Background for EnvironmentMap {
+    fn color(&self, ray: &Ray) -> Color<Linear> {
+        self.lookup(self.direction_to_uv(ray.direction()))
+    }
+
+    fn sample(&self) -> Option<BackgroundSample> {
+        let (uv, map_pdf) = self
+            .distribution
+            .sample(Vec2::new(f32::random(), f32::random()));
+        if map_pdf == 0.0 {
+            return None;
+        }
+
+        let sin_theta = (uv.y * PI).sin();
+        if sin_theta <= 0.0 {
+            return None;
+        }
+
+        Some(BackgroundSample {
+            direction: self.uv_to_direction(uv),
+            color: self.lookup(uv),
+            pdf: map_pdf / (2.0 * PI * PI * sin_theta),
+        })
+    }
+
+    fn pdf(&self, direction: Vec3) -> f32 {
+        let uv = self.direction_to_uv(direction);
+        let sin_theta = (uv.y * PI).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(uv) / (2.0 * PI * PI * sin_theta)
+    }
+}
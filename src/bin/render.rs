@@ -0,0 +1,222 @@
+use std::{env, fs, path::PathBuf, process::ExitCode, str::FromStr, sync::Arc};
+
+use image::ImageFormat;
+use ray_tracing::{default_extension, integrator, prelude::*};
+
+const USAGE: &str = "\
+Usage: render <scene> [options]
+
+Options:
+  -o, --output <path>            Output image path [default: output/<scene name>.<format>]
+  -f, --format <format>          png, jpeg, bmp, ppm, tga, exr or hdr [default: from the output path]
+  -w, --width <pixels>           Image width
+  -a, --aspect <ratio>           Aspect ratio, e.g. 1.5 or 16/9
+  -s, --spp <samples>            Samples per pixel
+  -d, --depth <bounces>          Maximum ray depth
+      --vfov <degrees>           Vertical field of view
+      --look-from <x,y,z>        Camera position
+      --look-at <x,y,z>          Camera target
+      --vup <x,y,z>              Camera up direction
+      --defocus-angle <degrees>  Defocus cone angle, 0 disables depth of field
+      --focus-distance <units>   Distance to the plane of perfect focus
+      --shutter <open> <close>   Time span rays are spread over, for motion blur
+      --background <r,g,b>       Solid background color
+      --integrator <name>        path, naive, direct, ao, normals or albedo
+  -h, --help                     Print this help
+";
+
+#[derive(Default)]
+struct Options {
+    scene: Option<PathBuf>,
+    output: Option<PathBuf>,
+    format: Option<ImageFormat>,
+    image_width: Option<u32>,
+    aspect_ratio: Option<f32>,
+    samples_per_pixel: Option<u32>,
+    max_depth: Option<u32>,
+    vfov: Option<f32>,
+    look_from: Option<Point3>,
+    look_at: Option<Point3>,
+    vup: Option<Vec3>,
+    defocus_angle: Option<f32>,
+    focus_distance: Option<f32>,
+    shutter: Option<(f32, f32)>,
+    background: Option<Arc<dyn Background>>,
+    integrator: Option<Arc<dyn Integrator>>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("`{}` needs a value", arg))
+            };
+
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "-o" | "--output" => options.output = Some(PathBuf::from(value()?)),
+                "-f" | "--format" => {
+                    let name = value()?;
+                    let format = ImageFormat::from_extension(&name)
+                        .filter(|format| format.writing_enabled())
+                        .ok_or_else(|| format!("unsupported image format `{}`", name))?;
+                    options.format = Some(format);
+                }
+                "-w" | "--width" => options.image_width = Some(parse(&arg, &value()?)?),
+                "-a" | "--aspect" => options.aspect_ratio = Some(parse_ratio(&arg, &value()?)?),
+                "-s" | "--spp" => options.samples_per_pixel = Some(parse(&arg, &value()?)?),
+                "-d" | "--depth" => options.max_depth = Some(parse(&arg, &value()?)?),
+                "--vfov" => options.vfov = Some(parse(&arg, &value()?)?),
+                "--look-from" => options.look_from = Some(parse_vec3(&arg, &value()?)?),
+                "--look-at" => options.look_at = Some(parse_vec3(&arg, &value()?)?),
+                "--vup" => options.vup = Some(parse_vec3(&arg, &value()?)?),
+                "--defocus-angle" => options.defocus_angle = Some(parse(&arg, &value()?)?),
+                "--focus-distance" => options.focus_distance = Some(parse(&arg, &value()?)?),
+                "--shutter" => {
+                    let open = parse(&arg, &value()?)?;
+                    options.shutter = Some((open, parse(&arg, &value()?)?));
+                }
+                "--background" => {
+                    let color = parse_vec3(&arg, &value()?)?;
+                    options.background = Some(Arc::new(Solid::new(Color::from(color))));
+                }
+                "--integrator" => {
+                    let name = value()?;
+                    let integrator = integrator::by_name(&name)
+                        .ok_or_else(|| format!("unknown integrator `{}`", name))?;
+                    options.integrator = Some(integrator);
+                }
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                _ if options.scene.is_none() => options.scene = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            }
+        }
+
+        Ok(Some(options))
+    }
+
+    fn apply(&self, camera: &mut CameraConfig) {
+        let Options {
+            image_width,
+            aspect_ratio,
+            samples_per_pixel,
+            max_depth,
+            vfov,
+            look_from,
+            look_at,
+            vup,
+            defocus_angle,
+            focus_distance,
+            ..
+        } = *self;
+
+        camera.image_width = image_width.unwrap_or(camera.image_width);
+        camera.aspect_ratio = aspect_ratio.unwrap_or(camera.aspect_ratio);
+        camera.samples_per_pixel = samples_per_pixel.unwrap_or(camera.samples_per_pixel);
+        camera.max_depth = max_depth.unwrap_or(camera.max_depth);
+        camera.vfov = vfov.unwrap_or(camera.vfov);
+        camera.look_from = look_from.unwrap_or(camera.look_from);
+        camera.look_at = look_at.unwrap_or(camera.look_at);
+        camera.vup = vup.unwrap_or(camera.vup);
+        camera.defocus_angle = defocus_angle.unwrap_or(camera.defocus_angle);
+        camera.focus_distance = focus_distance.unwrap_or(camera.focus_distance);
+        if let Some((open, close)) = self.shutter {
+            (camera.shutter_open, camera.shutter_close) = (open, close);
+        }
+        if let Some(background) = &self.background {
+            camera.background = background.clone();
+        }
+        if let Some(integrator) = &self.integrator {
+            camera.integrator = integrator.clone();
+        }
+    }
+}
+
+fn parse<T: FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{}` for `{}`", value, option))
+}
+
+fn parse_ratio(option: &str, value: &str) -> Result<f32, String> {
+    match value.split_once('/') {
+        Some((numerator, denominator)) => {
+            Ok(parse::<f32>(option, numerator)? / parse::<f32>(option, denominator)?)
+        }
+        None => parse(option, value),
+    }
+}
+
+fn parse_vec3(option: &str, value: &str) -> Result<Vec3, String> {
+    let components = value
+        .split(',')
+        .map(|component| parse::<f32>(option, component.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match components[..] {
+        [x, y, z] => Ok(Vec3::new(x, y, z)),
+        _ => Err(format!("`{}` expects three values as x,y,z", option)),
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let scene_path = options.scene.clone().ok_or("missing scene file")?;
+    let mut scene =
+        Scene::load(&scene_path).map_err(|e| format!("{}: {}", scene_path.display(), e))?;
+    options.apply(&mut scene.camera);
+
+    let format = match (options.format, &options.output) {
+        (Some(format), _) => format,
+        (None, Some(output)) if output.extension().is_some() => ImageFormat::from_path(output)
+            .map_err(|_| format!("cannot infer image format from `{}`", output.display()))?,
+        (None, _) => ImageFormat::Png,
+    };
+
+    let extension = default_extension(format);
+    let output = match options.output.clone() {
+        Some(output) if output.extension().is_none() && !output.is_dir() => {
+            output.with_extension(extension)
+        }
+        Some(output) => output,
+        None => {
+            let name = scene_path.file_stem().unwrap_or("image".as_ref());
+            PathBuf::from("output").join(format!("{}.{}", name.to_string_lossy(), extension))
+        }
+    };
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+
+    scene
+        .render_with_format(&output, format)
+        .map_err(|e| format!("{}: {}", output.display(), e))?;
+    println!("Saved {}", output.display());
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,13 +1,18 @@
-use image::RgbImage;
+use std::{ops::Range, sync::Arc};
+
+use image::Rgb32FImage;
 use indicatif::ParallelProgressIterator;
 use rayon::prelude::*;
 
 use crate::{
+    aabb::Aabb,
+    background::{Background, Gradient},
     color::{Color, Linear},
-    hittable::Hittable,
+    hittable::{constant_medium::Fog, Hittable},
+    integrator::{path::PathTracer, Integrator, RenderContext},
     point::Point3,
     ray::Ray,
-    utils::{Random, INFINITY},
+    utils::Random,
     vec::{Vec3, VecExt},
 };
 
@@ -23,6 +28,10 @@ pub struct Camera {
     defocus_angle: f32,
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
+    shutter: Range<f32>,
+    background: Arc<dyn Background>,
+    fog: Option<Fog>,
+    integrator: Arc<dyn Integrator>,
 }
 
 impl Camera {
@@ -38,6 +47,11 @@ impl Camera {
             vup,
             defocus_angle,
             focus_distance,
+            shutter_open,
+            shutter_close,
+            ref background,
+            ref fog,
+            ref integrator,
         }: &CameraConfig,
     ) -> Self {
         let image_height = (image_width as f32 / aspect_ratio) as u32;
@@ -83,11 +97,24 @@ impl Camera {
             defocus_angle,
             defocus_disk_u,
             defocus_disk_v,
+            shutter: shutter_open..shutter_close,
+            background: background.clone(),
+            fog: fog.clone(),
+            integrator: integrator.clone(),
         }
     }
 
-    pub fn render(&self, world: &impl Hittable) -> RgbImage {
-        let mut output = RgbImage::new(self.image_width, self.image_height);
+    // Linear radiance of every pixel.
+    pub fn render(&self, world: &impl Hittable, lights: &impl Hittable) -> Rgb32FImage {
+        let context = RenderContext {
+            world,
+            lights,
+            background: self.background.as_ref(),
+            fog: self.fog.as_ref(),
+            max_depth: self.max_depth,
+        };
+
+        let mut output = Rgb32FImage::new(self.image_width, self.image_height);
         output
             .par_enumerate_pixels_mut()
             .progress()
@@ -95,33 +122,17 @@ impl Camera {
                 let mut color: Color<Linear> =
                     (0..self.samples_per_pixel).fold(Color::from(Vec3::ZERO), |acc, _| {
                         let ray = self.get_ray(x, y);
-                        Color::from(acc.v + Self::ray_color(&ray, world, self.max_depth).v)
+                        let sample = self.integrator.li(&ray, &context);
+                        Color::from(acc.v + sample.v)
                     });
 
                 color.v *= 1.0 / self.samples_per_pixel as f32;
-                *pixel = color.to_srgb().into();
+                pixel.0 = color.v.to_array();
             });
 
         output
     }
 
-    fn ray_color(ray: &Ray, world: &impl Hittable, depth: u32) -> Color<Linear> {
-        if depth == 0 {
-            return Color::from(Vec3::ZERO);
-        }
-
-        if let Some(hit) = world.hit(ray, 0.001..INFINITY) {
-            let Some((scattered, attenuation)) = hit.material.scatter(ray, &hit) else {
-                return Color::from(Vec3::ZERO);
-            };
-            return Color::from(attenuation.v * Self::ray_color(&scattered, world, depth - 1).v);
-        }
-
-        let unit_direction = ray.direction().normalize();
-        let a = 0.5 * (unit_direction.y + 1.0);
-        Color::from(Vec3::lerp(Vec3::ONE, Vec3::new(0.5, 0.7, 1.0), a))
-    }
-
     fn get_ray(&self, i: u32, j: u32) -> Ray {
         let offset = Camera::sample_square();
         let pixel_sample = self.pixel00_origin
@@ -134,8 +145,9 @@ impl Camera {
             self.defocus_disk_sample()
         };
         let ray_direction = pixel_sample - ray_origin;
+        let ray_time = self.shutter.start + f32::random() * (self.shutter.end - self.shutter.start);
 
-        Ray::new(ray_origin, ray_direction)
+        Ray::new(ray_origin, ray_direction).with_time(ray_time)
     }
 
     fn sample_square() -> Vec3 {
@@ -159,6 +171,38 @@ pub struct CameraConfig {
     pub vup: Vec3,
     pub defocus_angle: f32,
     pub focus_distance: f32,
+    // Rays are spread over this interval; objects move between time 0 and 1.
+    pub shutter_open: f32,
+    pub shutter_close: f32,
+    pub background: Arc<dyn Background>,
+    // Homogeneous medium filling the whole scene.
+    pub fog: Option<Fog>,
+    pub integrator: Arc<dyn Integrator>,
+}
+
+impl CameraConfig {
+    pub fn framing(self, bbox: &Aabb) -> Self {
+        let (center, radius) = bbox.bounding_sphere();
+        if bbox.is_empty() || !radius.is_finite() || radius <= 0.0 {
+            return self;
+        }
+
+        let direction = (self.look_from - self.look_at)
+            .try_normalize()
+            .unwrap_or(Vec3::Z);
+
+        // Fit the bounding sphere into the narrower of the two fields of view.
+        let half_vfov = (self.vfov / 2.0).to_radians();
+        let half_hfov = (half_vfov.tan() * self.aspect_ratio).atan();
+        let distance = radius / half_vfov.min(half_hfov).sin();
+
+        Self {
+            look_from: center + distance * direction,
+            look_at: center,
+            focus_distance: distance,
+            ..self
+        }
+    }
 }
 
 impl Default for CameraConfig {
@@ -174,6 +218,11 @@ impl Default for CameraConfig {
             vup: Vec3::Y,
             defocus_angle: 0.0,
             focus_distance: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            background: Arc::new(Gradient::default()),
+            fog: None,
+            integrator: Arc::new(PathTracer),
         }
     }
 }
@@ -1,15 +1,43 @@
+pub mod bvh;
+pub mod cone;
+pub mod constant_medium;
+pub mod cuboid;
+pub mod cylinder;
+pub mod disk;
+pub mod mesh;
+pub mod plane;
+pub mod quad;
 pub mod sphere;
+pub mod torus;
+pub mod transformed;
+pub mod triangle;
 
 use std::{ops::Range, sync::Arc};
 
-use crate::{material::Material, point::Point3, ray::Ray, vec::Vec3};
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::Random,
+    vec::{Vec2, Vec3},
+};
 
+// Normals face the incoming ray. `normal` is the shading normal materials work with, which
+// interpolated or mapped normals can tilt away from the true surface in `geometric_normal`.
 #[derive(Debug)]
 pub struct HitRecord {
     pub point: Point3,
     pub normal: Vec3,
+    pub geometric_normal: Vec3,
     pub t: f32,
+    pub uv: Vec2,
+    // Surface derivatives with respect to `uv`; not normalized.
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
     pub front_face: bool,
+    // Scattering inside a participating medium, where there is no surface to speak of.
+    pub volumetric: bool,
     pub material: Arc<dyn Material>,
 }
 
@@ -23,24 +51,127 @@ impl HitRecord {
     ) -> HitRecord {
         let front_face = direction.dot(normal) < 0.0;
         let normal = if front_face { normal } else { -normal };
+        let (dpdu, dpdv) = normal.any_orthonormal_pair();
 
         HitRecord {
             point,
             normal,
+            geometric_normal: normal,
             t,
+            uv: Vec2::ZERO,
+            dpdu,
+            dpdv,
             front_face,
+            volumetric: false,
             material,
         }
     }
+
+    // Interaction at `point` inside a medium. The normal faces back along the ray only so that
+    // code expecting one gets something sensible.
+    pub fn volumetric(
+        direction: Vec3,
+        point: Point3,
+        t: f32,
+        material: Arc<dyn Material>,
+    ) -> HitRecord {
+        let normal = -direction.normalize();
+        HitRecord {
+            volumetric: true,
+            ..HitRecord::new(direction, point, normal, t, material)
+        }
+    }
+
+    // Cosine factor between the surface and light travelling along `direction`; media have
+    // none.
+    pub fn cosine(&self, direction: Vec3) -> f32 {
+        if self.volumetric {
+            1.0
+        } else {
+            self.normal.dot(direction.normalize()).abs()
+        }
+    }
+
+    pub fn with_uv(self, uv: Vec2, dpdu: Vec3, dpdv: Vec3) -> Self {
+        Self {
+            uv,
+            dpdu,
+            dpdv,
+            ..self
+        }
+    }
+
+    // Replaces the shading normal, keeping it on the same side as the geometric one.
+    pub fn with_shading_normal(self, normal: Vec3) -> Self {
+        let Some(normal) = normal.try_normalize() else {
+            return self;
+        };
+        let normal = if normal.dot(self.geometric_normal) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        Self { normal, ..self }
+    }
 }
 
 pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord>;
+
+    fn bounding_box(&self) -> Aabb;
+
+    // Solid-angle density of `random` picking `direction` from `origin` at `time`; 0 if it
+    // can't be sampled.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3, _time: f32) -> f32 {
+        0.0
+    }
+
+    fn random(&self, _origin: Point3, _time: f32) -> Vec3 {
+        Vec3::X
+    }
 }
 
 pub type HittableList = Vec<Box<dyn Hittable>>;
 
-impl Hittable for HittableList {
+pub type LightList = Vec<Arc<dyn Hittable>>;
+
+impl<H: Hittable + ?Sized> Hittable for Box<H> {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        (**self).hit(ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f32) -> f32 {
+        (**self).pdf_value(origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f32) -> Vec3 {
+        (**self).random(origin, time)
+    }
+}
+
+impl<H: Hittable + ?Sized> Hittable for Arc<H> {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        (**self).hit(ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f32) -> f32 {
+        (**self).pdf_value(origin, direction, time)
+    }
+
+    fn random(&self, origin: Point3, time: f32) -> Vec3 {
+        (**self).random(origin, time)
+    }
+}
+
+impl<H: Hittable> Hittable for Vec<H> {
     fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
         let mut closest_so_far = ray_t.end;
         let mut hit_record = None;
@@ -54,4 +185,50 @@ impl Hittable for HittableList {
 
         hit_record
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.iter()
+            .fold(Aabb::EMPTY, |acc, object| acc.union(&object.bounding_box()))
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f32) -> f32 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f32 = self
+            .iter()
+            .map(|object| object.pdf_value(origin, direction, time))
+            .sum();
+        sum / self.len() as f32
+    }
+
+    fn random(&self, origin: Point3, time: f32) -> Vec3 {
+        if self.is_empty() {
+            return Vec3::X;
+        }
+
+        let index = ((f32::random() * self.len() as f32) as usize).min(self.len() - 1);
+        self[index].random(origin, time)
+    }
+}
+
+// Real roots of a·t² + b·t + c in increasing order, using the form that avoids cancellation.
+fn solve_quadratic(a: f32, b: f32, c: f32) -> Option<(f32, f32)> {
+    if a == 0.0 {
+        if b == 0.0 {
+            return None;
+        }
+        let t = -c / b;
+        return Some((t, t));
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let (t0, t1) = if q == 0.0 { (0.0, 0.0) } else { (q / a, c / q) };
+    Some((t0.min(t1), t0.max(t1)))
 }
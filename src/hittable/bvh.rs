@@ -0,0 +1,315 @@
+use std::ops::Range;
+
+use crate::{aabb::Aabb, point::Point3, ray::Ray};
+
+use super::{HitRecord, Hittable, HittableList};
+
+const MAX_LEAF_SIZE: usize = 4;
+const SAH_BUCKETS: usize = 12;
+const SAH_TRAVERSAL_COST: f32 = 0.125;
+// Past this depth splits fall back to the median so the traversal stack stays bounded.
+const MAX_UNBALANCED_DEPTH: usize = 32;
+const STACK_SIZE: usize = 64;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplitMethod {
+    Midpoint,
+    #[default]
+    Sah,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum NodeKind {
+    Leaf { start: usize, count: usize },
+    Interior { second_child: usize, axis: usize },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bbox: Aabb,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Copy)]
+struct PrimitiveInfo {
+    index: usize,
+    bbox: Aabb,
+    centroid: Point3,
+}
+
+// Unbounded primitives can't be split spatially; they follow the bounded ones in `primitives`
+// and are tested against every ray.
+pub struct Bvh<H: Hittable = Box<dyn Hittable>> {
+    nodes: Vec<Node>,
+    primitives: Vec<H>,
+    bounded: usize,
+}
+
+impl<H: Hittable> Bvh<H> {
+    pub fn new(primitives: Vec<H>, split_method: SplitMethod) -> Bvh<H> {
+        let (mut infos, unbounded): (Vec<PrimitiveInfo>, Vec<PrimitiveInfo>) = primitives
+            .iter()
+            .enumerate()
+            .map(|(index, primitive)| {
+                let bbox = primitive.bounding_box();
+                PrimitiveInfo {
+                    index,
+                    bbox,
+                    centroid: bbox.centroid(),
+                }
+            })
+            .partition(|info| !info.bbox.is_unbounded());
+
+        let mut nodes = Vec::with_capacity(2 * primitives.len());
+        if !infos.is_empty() {
+            build(&mut nodes, &mut infos, 0, split_method, 0);
+        }
+
+        let mut slots: Vec<Option<H>> = primitives.into_iter().map(Some).collect();
+        let primitives = infos
+            .iter()
+            .chain(&unbounded)
+            .map(|info| slots[info.index].take().expect("primitive is used once"))
+            .collect();
+
+        Bvh {
+            nodes,
+            primitives,
+            bounded: infos.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    pub fn primitives(&self) -> &[H] {
+        &self.primitives
+    }
+}
+
+impl From<HittableList> for Bvh {
+    fn from(list: HittableList) -> Self {
+        Bvh::new(list, SplitMethod::default())
+    }
+}
+
+fn build(
+    nodes: &mut Vec<Node>,
+    infos: &mut [PrimitiveInfo],
+    offset: usize,
+    split_method: SplitMethod,
+    depth: usize,
+) -> usize {
+    let bbox = infos
+        .iter()
+        .fold(Aabb::EMPTY, |acc, info| acc.union(&info.bbox));
+    let node_index = nodes.len();
+    nodes.push(Node {
+        bbox,
+        kind: NodeKind::Leaf {
+            start: offset,
+            count: infos.len(),
+        },
+    });
+
+    if infos.len() <= 1 {
+        return node_index;
+    }
+
+    let centroid_bounds = infos
+        .iter()
+        .fold(Aabb::EMPTY, |acc, info| acc.union_point(info.centroid));
+    let axis = centroid_bounds.longest_axis();
+    let (min, max) = (centroid_bounds.min[axis], centroid_bounds.max[axis]);
+
+    if max <= min {
+        return node_index;
+    }
+
+    let mid = if depth >= MAX_UNBALANCED_DEPTH {
+        split_median(infos, axis)
+    } else {
+        match split_method {
+            SplitMethod::Midpoint => {
+                let pivot = 0.5 * (min + max);
+                match partition(infos, |info| info.centroid[axis] < pivot) {
+                    0 => split_median(infos, axis),
+                    mid if mid == infos.len() => split_median(infos, axis),
+                    mid => mid,
+                }
+            }
+            SplitMethod::Sah => match split_sah(infos, &bbox, axis, min, max) {
+                Some(mid) => mid,
+                None => return node_index,
+            },
+        }
+    };
+
+    let (left, right) = infos.split_at_mut(mid);
+    build(nodes, left, offset, split_method, depth + 1);
+    let second_child = build(nodes, right, offset + mid, split_method, depth + 1);
+    nodes[node_index].kind = NodeKind::Interior { second_child, axis };
+
+    node_index
+}
+
+fn split_median(infos: &mut [PrimitiveInfo], axis: usize) -> usize {
+    let mid = infos.len() / 2;
+    infos.select_nth_unstable_by(mid, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+    mid
+}
+
+fn split_sah(
+    infos: &mut [PrimitiveInfo],
+    bbox: &Aabb,
+    axis: usize,
+    min: f32,
+    max: f32,
+) -> Option<usize> {
+    if infos.len() <= 2 {
+        return Some(split_median(infos, axis));
+    }
+
+    let bucket_of = |info: &PrimitiveInfo| {
+        let b = ((info.centroid[axis] - min) / (max - min) * SAH_BUCKETS as f32) as usize;
+        b.min(SAH_BUCKETS - 1)
+    };
+
+    let mut counts = [0usize; SAH_BUCKETS];
+    let mut bounds = [Aabb::EMPTY; SAH_BUCKETS];
+    for info in infos.iter() {
+        let b = bucket_of(info);
+        counts[b] += 1;
+        bounds[b] = bounds[b].union(&info.bbox);
+    }
+
+    let mut below_area = [0.0; SAH_BUCKETS - 1];
+    let mut below_count = [0usize; SAH_BUCKETS - 1];
+    let (mut acc_bbox, mut acc_count) = (Aabb::EMPTY, 0);
+    for i in 0..SAH_BUCKETS - 1 {
+        acc_bbox = acc_bbox.union(&bounds[i]);
+        acc_count += counts[i];
+        below_area[i] = acc_bbox.surface_area();
+        below_count[i] = acc_count;
+    }
+
+    let (mut best_bucket, mut best_cost) = (0, f32::INFINITY);
+    let (mut acc_bbox, mut acc_count) = (Aabb::EMPTY, 0);
+    for i in (0..SAH_BUCKETS - 1).rev() {
+        acc_bbox = acc_bbox.union(&bounds[i + 1]);
+        acc_count += counts[i + 1];
+        let cost =
+            below_count[i] as f32 * below_area[i] + acc_count as f32 * acc_bbox.surface_area();
+        if cost < best_cost {
+            best_bucket = i;
+            best_cost = cost;
+        }
+    }
+
+    let total_area = bbox.surface_area();
+    let split_cost = if total_area > 0.0 {
+        SAH_TRAVERSAL_COST + best_cost / total_area
+    } else {
+        SAH_TRAVERSAL_COST
+    };
+    let leaf_cost = infos.len() as f32;
+
+    if infos.len() <= MAX_LEAF_SIZE && leaf_cost <= split_cost {
+        return None;
+    }
+
+    match partition(infos, |info| bucket_of(info) <= best_bucket) {
+        0 => Some(split_median(infos, axis)),
+        mid if mid == infos.len() => Some(split_median(infos, axis)),
+        mid => Some(mid),
+    }
+}
+
+fn partition<T>(slice: &mut [T], predicate: impl Fn(&T) -> bool) -> usize {
+    let mut mid = 0;
+    for i in 0..slice.len() {
+        if predicate(&slice[i]) {
+            slice.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
+
+impl<H: Hittable> Hittable for Bvh<H> {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let mut closest_so_far = ray_t.end;
+        let mut hit_record = None;
+
+        for primitive in &self.primitives[self.bounded..] {
+            if let Some(record) = primitive.hit(ray, ray_t.start..closest_so_far) {
+                closest_so_far = record.t;
+                hit_record = Some(record);
+            }
+        }
+
+        if self.nodes.is_empty() {
+            return hit_record;
+        }
+
+        let direction_is_negative = [
+            ray.direction.x < 0.0,
+            ray.direction.y < 0.0,
+            ray.direction.z < 0.0,
+        ];
+
+        let mut stack = [0usize; STACK_SIZE];
+        let mut stack_len = 0;
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+            if node.bbox.hit(ray, ray_t.start..closest_so_far) {
+                match node.kind {
+                    NodeKind::Leaf { start, count } => {
+                        for primitive in &self.primitives[start..start + count] {
+                            if let Some(record) = primitive.hit(ray, ray_t.start..closest_so_far) {
+                                closest_so_far = record.t;
+                                hit_record = Some(record);
+                            }
+                        }
+                    }
+                    NodeKind::Interior { second_child, axis } => {
+                        // Visit the child nearer to the ray origin first.
+                        if direction_is_negative[axis] {
+                            stack[stack_len] = current + 1;
+                            current = second_child;
+                        } else {
+                            stack[stack_len] = second_child;
+                            current += 1;
+                        }
+                        stack_len += 1;
+                        continue;
+                    }
+                }
+            }
+
+            if stack_len == 0 {
+                break;
+            }
+            stack_len -= 1;
+            current = stack[stack_len];
+        }
+
+        hit_record
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let bounded = self.nodes.first().map_or(Aabb::EMPTY, |node| node.bbox);
+        self.primitives[self.bounded..]
+            .iter()
+            .fold(bounded, |acc, primitive| {
+                acc.union(&primitive.bounding_box())
+            })
+    }
+}
@@ -0,0 +1,99 @@
+use std::{f32::consts::TAU, ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::RangeExt,
+    vec::{Onb, Vec2, Vec3},
+};
+
+use super::{
+    cylinder::LocalHit,
+    disk::{circle_bounds, disk_uv},
+    solve_quadratic, HitRecord, Hittable,
+};
+
+// Cone with a capped circular base. On the side u goes around the axis and v from the base
+// to the apex; the cap uses polar UVs like `Disk`.
+pub struct Cone {
+    base: Point3,
+    // `w` runs along the axis from `base` to the apex.
+    frame: Onb,
+    height: f32,
+    radius: f32,
+    material: Arc<dyn Material>,
+}
+
+impl Cone {
+    pub fn new(base: Point3, apex: Point3, radius: f32, material: Arc<dyn Material>) -> Cone {
+        Cone {
+            base,
+            frame: Onb::new(apex - base),
+            height: (apex - base).length(),
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let origin = self.frame.to_local(ray.origin - self.base);
+        let direction = self.frame.to_local(ray.direction);
+        let mut closest: Option<LocalHit> = None;
+
+        // x² + y² = (k·(h - z))², where k is the slope of the radius.
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let h = self.height - origin.z;
+        let a =
+            direction.x * direction.x + direction.y * direction.y - k2 * direction.z * direction.z;
+        let b = 2.0 * (origin.x * direction.x + origin.y * direction.y + k2 * h * direction.z);
+        let c = origin.x * origin.x + origin.y * origin.y - k2 * h * h;
+        if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+            for t in [t0, t1] {
+                let p = origin + t * direction;
+                if ray_t.surrounds(&t) && (0.0..=self.height).contains(&p.z) {
+                    let phi = p.y.atan2(p.x).rem_euclid(TAU);
+                    let distance = k * (self.height - p.z);
+                    let (cos_phi, sin_phi) = (phi.cos(), phi.sin());
+                    closest = Some(LocalHit {
+                        t,
+                        normal: Vec3::new(p.x, p.y, k * distance),
+                        uv: Vec2::new(phi / TAU, p.z / self.height),
+                        dpdu: TAU * Vec3::new(-p.y, p.x, 0.0),
+                        dpdv: Vec3::new(
+                            -self.radius * cos_phi,
+                            -self.radius * sin_phi,
+                            self.height,
+                        ),
+                    });
+                    break;
+                }
+            }
+        }
+
+        let t = -origin.z / direction.z;
+        let p = origin + t * direction;
+        let nearer = closest.as_ref().is_none_or(|closest| t < closest.t);
+        if nearer && ray_t.surrounds(&t) && p.x * p.x + p.y * p.y <= self.radius * self.radius {
+            let (uv, dpdu, dpdv) = disk_uv(p, self.radius);
+            closest = Some(LocalHit {
+                t,
+                normal: -Vec3::Z,
+                uv,
+                dpdu,
+                dpdv,
+            });
+        }
+
+        Some(closest?.to_world(ray, &self.frame, self.material.clone()))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let apex = self.base + self.height * self.frame.w;
+        circle_bounds(self.base, self.frame.w, self.radius).union_point(apex)
+    }
+}
@@ -0,0 +1,95 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    ray::Ray,
+    utils::{Random, INFINITY},
+};
+
+use super::{HitRecord, Hittable};
+
+// Homogeneous participating medium filling `boundary`, such as smoke or a milky blob. Rays
+// passing through scatter off the `phase` material at exponentially distributed distances.
+// The boundary has to be closed and convex, since only its first entry and exit are found.
+pub struct ConstantMedium<H: Hittable> {
+    boundary: H,
+    density: f32,
+    phase: Arc<dyn Material>,
+}
+
+impl<H: Hittable> ConstantMedium<H> {
+    pub fn new(boundary: H, density: f32, phase: Arc<dyn Material>) -> ConstantMedium<H> {
+        ConstantMedium {
+            boundary,
+            density,
+            phase,
+        }
+    }
+}
+
+impl<H: Hittable> Hittable for ConstantMedium<H> {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        // The entry may lie behind the ray when it starts inside the medium.
+        let entry = self.boundary.hit(ray, -INFINITY..INFINITY)?;
+        let exit = self.boundary.hit(ray, entry.t + 0.0001..INFINITY)?;
+
+        let start = entry.t.max(ray_t.start).max(0.0);
+        let end = exit.t.min(ray_t.end);
+        if start >= end {
+            return None;
+        }
+
+        let t = start + free_path(self.density) / ray.direction.length();
+        (t < end).then(|| HitRecord::volumetric(ray.direction, ray.at(t), t, self.phase.clone()))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
+
+// Homogeneous medium filling the whole scene, thickening with distance like fog or haze. It
+// stretches up to `max_distance` from where each ray starts, and rays that reach the background
+// without hitting anything only pass through that much of it; with no limit they see the
+// background clear, as if the fog ended just beyond the farthest surface.
+#[derive(Clone, Debug)]
+pub struct Fog {
+    density: f32,
+    phase: Arc<dyn Material>,
+    max_distance: f32,
+}
+
+impl Fog {
+    pub fn new(density: f32, phase: Arc<dyn Material>) -> Fog {
+        Fog {
+            density,
+            phase,
+            max_distance: INFINITY,
+        }
+    }
+
+    pub fn with_max_distance(self, max_distance: f32) -> Fog {
+        Fog {
+            max_distance,
+            ..self
+        }
+    }
+
+    // Scattering event along `ray` within `ray_t`, if one happens before the end.
+    pub fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let length = ray.direction.length();
+        let end = ray_t.end.min(ray_t.start + self.max_distance / length);
+        if !end.is_finite() {
+            return None;
+        }
+
+        let t = ray_t.start + free_path(self.density) / length;
+        (t < end).then(|| HitRecord::volumetric(ray.direction, ray.at(t), t, self.phase.clone()))
+    }
+}
+
+// Distance travelled through a medium of `density` before scattering.
+fn free_path(density: f32) -> f32 {
+    -(1.0 - f32::random()).ln() / density
+}
@@ -0,0 +1,109 @@
+use std::{ops::Range, sync::Arc};
+
+use glam::Mat3A;
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::RangeExt,
+    vec::{Vec2, Vec3},
+};
+
+use super::{HitRecord, Hittable};
+
+// Rectangular box, axis-aligned or rotated about its center. Each face has its own UVs
+// running from 0 to 1 across it.
+pub struct Cuboid {
+    center: Point3,
+    half_extents: Vec3,
+    // Columns are the box's axes in world space.
+    rotation: Mat3A,
+    material: Arc<dyn Material>,
+}
+
+impl Cuboid {
+    // Axis-aligned box with opposite corners `a` and `b`.
+    pub fn new(a: Point3, b: Point3, material: Arc<dyn Material>) -> Cuboid {
+        Cuboid::oriented(
+            0.5 * (a + b),
+            0.5 * (b - a).abs(),
+            Mat3A::IDENTITY,
+            material,
+        )
+    }
+
+    // `rotation` must be a pure rotation.
+    pub fn oriented(
+        center: Point3,
+        half_extents: Vec3,
+        rotation: Mat3A,
+        material: Arc<dyn Material>,
+    ) -> Cuboid {
+        Cuboid {
+            center,
+            half_extents,
+            rotation,
+            material,
+        }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let to_local = self.rotation.transpose();
+        let origin = to_local * (ray.origin - self.center);
+        let direction = to_local * ray.direction;
+
+        // Slab test, keeping track of which axis bounds the entry and exit.
+        let inv_direction = direction.recip();
+        let t0 = (-self.half_extents - origin) * inv_direction;
+        let t1 = (self.half_extents - origin) * inv_direction;
+        let (near, far) = (t0.min(t1), t0.max(t1));
+        let t_enter = near.max_element();
+        let t_exit = far.min_element();
+        if t_enter > t_exit {
+            return None;
+        }
+
+        let (t, axis) = if ray_t.surrounds(&t_enter) {
+            (t_enter, near.to_array().iter().position(|&t| t == t_enter)?)
+        } else if ray_t.surrounds(&t_exit) {
+            (t_exit, far.to_array().iter().position(|&t| t == t_exit)?)
+        } else {
+            return None;
+        };
+
+        let local = origin + t * direction;
+        let mut outward_normal = Vec3::ZERO;
+        outward_normal[axis] = local[axis].signum();
+
+        let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+        let size = 2.0 * self.half_extents;
+        let uv = Vec2::new(
+            (local[b] + self.half_extents[b]) / size[b],
+            (local[c] + self.half_extents[c]) / size[c],
+        );
+        let dpdu = size[b] * self.rotation.col(b);
+        let dpdv = size[c] * self.rotation.col(c);
+
+        let hit_rec = HitRecord::new(
+            ray.direction,
+            ray.at(t),
+            self.rotation * outward_normal,
+            t,
+            self.material.clone(),
+        )
+        .with_uv(uv, dpdu, dpdv);
+
+        Some(hit_rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = self.rotation.x_axis.abs() * self.half_extents.x
+            + self.rotation.y_axis.abs() * self.half_extents.y
+            + self.rotation.z_axis.abs() * self.half_extents.z;
+        Aabb::new(self.center - extent, self.center + extent)
+    }
+}
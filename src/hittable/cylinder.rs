@@ -0,0 +1,117 @@
+use std::{f32::consts::TAU, ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::RangeExt,
+    vec::{Onb, Vec2, Vec3},
+};
+
+use super::{
+    disk::{circle_bounds, disk_uv},
+    solve_quadratic, HitRecord, Hittable,
+};
+
+// Capped cylinder between the centers of its two end caps. On the side u goes around the
+// axis and v along it; the caps use polar UVs like `Disk`.
+pub struct Cylinder {
+    base: Point3,
+    // `w` runs along the axis from `base`.
+    frame: Onb,
+    height: f32,
+    radius: f32,
+    material: Arc<dyn Material>,
+}
+
+impl Cylinder {
+    pub fn new(base: Point3, top: Point3, radius: f32, material: Arc<dyn Material>) -> Cylinder {
+        Cylinder {
+            base,
+            frame: Onb::new(top - base),
+            height: (top - base).length(),
+            radius,
+            material,
+        }
+    }
+}
+
+// Intersection worked out in a shape's local frame, with an outward normal that need not be
+// normalized.
+pub(super) struct LocalHit {
+    pub t: f32,
+    pub normal: Vec3,
+    pub uv: Vec2,
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
+}
+
+impl LocalHit {
+    pub fn to_world(&self, ray: &Ray, frame: &Onb, material: Arc<dyn Material>) -> HitRecord {
+        let normal = frame
+            .to_world(self.normal)
+            .try_normalize()
+            .unwrap_or(frame.w);
+        HitRecord::new(ray.direction, ray.at(self.t), normal, self.t, material).with_uv(
+            self.uv,
+            frame.to_world(self.dpdu),
+            frame.to_world(self.dpdv),
+        )
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let origin = self.frame.to_local(ray.origin - self.base);
+        let direction = self.frame.to_local(ray.direction);
+        let mut closest: Option<LocalHit> = None;
+
+        let a = direction.x * direction.x + direction.y * direction.y;
+        let b = 2.0 * (origin.x * direction.x + origin.y * direction.y);
+        let c = origin.x * origin.x + origin.y * origin.y - self.radius * self.radius;
+        if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+            for t in [t0, t1] {
+                let p = origin + t * direction;
+                if ray_t.surrounds(&t) && (0.0..=self.height).contains(&p.z) {
+                    let phi = p.y.atan2(p.x).rem_euclid(TAU);
+                    closest = Some(LocalHit {
+                        t,
+                        normal: Vec3::new(p.x, p.y, 0.0),
+                        uv: Vec2::new(phi / TAU, p.z / self.height),
+                        dpdu: TAU * Vec3::new(-p.y, p.x, 0.0),
+                        dpdv: Vec3::new(0.0, 0.0, self.height),
+                    });
+                    break;
+                }
+            }
+        }
+
+        for (z, normal) in [(0.0, -Vec3::Z), (self.height, Vec3::Z)] {
+            let t = (z - origin.z) / direction.z;
+            let p = origin + t * direction;
+            let nearer = closest.as_ref().is_none_or(|closest| t < closest.t);
+            if nearer && ray_t.surrounds(&t) && p.x * p.x + p.y * p.y <= self.radius * self.radius {
+                let (uv, dpdu, dpdv) = disk_uv(p, self.radius);
+                closest = Some(LocalHit {
+                    t,
+                    normal,
+                    uv,
+                    dpdu,
+                    dpdv,
+                });
+            }
+        }
+
+        Some(closest?.to_world(ray, &self.frame, self.material.clone()))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let top = self.base + self.height * self.frame.w;
+        circle_bounds(self.base, self.frame.w, self.radius).union(&circle_bounds(
+            top,
+            self.frame.w,
+            self.radius,
+        ))
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    f32::consts::{PI, TAU},
+    ops::Range,
+    sync::Arc,
+};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::{RangeExt, INFINITY},
+    vec::{Onb, Vec2, Vec3, VecExt},
+};
+
+use super::{
+    triangle::{area_pdf, BOUNDS_PADDING},
+    HitRecord, Hittable,
+};
+
+pub struct Disk {
+    center: Point3,
+    frame: Onb,
+    radius: f32,
+    material: Arc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: f32, material: Arc<dyn Material>) -> Disk {
+        Disk {
+            center,
+            frame: Onb::new(normal),
+            radius,
+            material,
+        }
+    }
+}
+
+// Polar UVs of a point `local` in a disk's plane: u is the angle around the center, v the
+// distance from it as a fraction of `radius`. The derivatives are in the same local frame.
+pub(super) fn disk_uv(local: Vec3, radius: f32) -> (Vec2, Vec3, Vec3) {
+    let distance = local.truncate().length();
+    let phi = local.y.atan2(local.x).rem_euclid(TAU);
+    let uv = Vec2::new(phi / TAU, distance / radius);
+
+    let dpdu = TAU * Vec3::new(-local.y, local.x, 0.0);
+    let dpdv = if distance > 0.0 {
+        radius / distance * Vec3::new(local.x, local.y, 0.0)
+    } else {
+        radius * Vec3::X
+    };
+
+    (uv, dpdu, dpdv)
+}
+
+// Bounds of a circle of `radius` around `center` in the plane with unit normal `axis`.
+pub(super) fn circle_bounds(center: Point3, axis: Vec3, radius: f32) -> Aabb {
+    let extent = radius * (Vec3::ONE - axis * axis).max(Vec3::ZERO).powf(0.5);
+    Aabb::new(center - extent, center + extent)
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let normal = self.frame.w;
+        let denominator = normal.dot(ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = normal.dot(self.center - ray.origin) / denominator;
+        if !ray_t.surrounds(&t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let local = self.frame.to_local(point - self.center);
+        if local.truncate().length_squared() > self.radius * self.radius {
+            return None;
+        }
+
+        let (uv, dpdu, dpdv) = disk_uv(local, self.radius);
+        let hit_rec = HitRecord::new(ray.direction, point, normal, t, self.material.clone())
+            .with_uv(uv, self.frame.to_world(dpdu), self.frame.to_world(dpdv));
+
+        Some(hit_rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        circle_bounds(self.center, self.frame.w, self.radius).expand(BOUNDS_PADDING)
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, _time: f32) -> f32 {
+        let area = PI * self.radius * self.radius;
+        match self.hit(&Ray::new(origin, direction), 0.001..INFINITY) {
+            Some(hit) => area_pdf(direction, hit.t, self.frame.w, area),
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: Point3, _time: f32) -> Vec3 {
+        let local = self.radius * Vec3::random_in_unit_disk();
+        self.center + self.frame.to_world(local) - origin
+    }
+}
@@ -0,0 +1,191 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    sampling::Distribution1D,
+    utils::{Random, INFINITY},
+    vec::{Vec2, Vec3},
+};
+
+use super::{
+    bvh::{Bvh, SplitMethod},
+    triangle, HitRecord, Hittable,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct MeshBuffers {
+    pub positions: Vec<Point3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<Vec2>,
+    pub indices: Vec<[u32; 3]>,
+}
+
+struct MeshData {
+    buffers: MeshBuffers,
+    material: Arc<dyn Material>,
+}
+
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    index: usize,
+}
+
+pub struct TriangleMesh {
+    bvh: Bvh<MeshTriangle>,
+    areas: Distribution1D,
+    total_area: f32,
+}
+
+impl TriangleMesh {
+    pub fn new(buffers: MeshBuffers, material: Arc<dyn Material>) -> TriangleMesh {
+        let vertex_count = buffers.positions.len();
+        assert!(
+            buffers.normals.is_empty() || buffers.normals.len() == vertex_count,
+            "mesh normals must be empty or match the vertex count"
+        );
+        assert!(
+            buffers.uvs.is_empty() || buffers.uvs.len() == vertex_count,
+            "mesh uvs must be empty or match the vertex count"
+        );
+        assert!(
+            buffers
+                .indices
+                .iter()
+                .flatten()
+                .all(|&i| (i as usize) < vertex_count),
+            "mesh index out of range"
+        );
+
+        let triangle_count = buffers.indices.len();
+        let mesh = Arc::new(MeshData { buffers, material });
+        let triangles = (0..triangle_count)
+            .map(|index| MeshTriangle {
+                mesh: mesh.clone(),
+                index,
+            })
+            .collect();
+
+        let bvh = Bvh::new(triangles, SplitMethod::Sah);
+        let areas: Vec<f32> = bvh
+            .primitives()
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.positions();
+                triangle::area(a, b, c)
+            })
+            .collect();
+        let total_area = areas.iter().sum();
+
+        TriangleMesh {
+            bvh,
+            areas: Distribution1D::new(if areas.is_empty() { vec![0.0] } else { areas }),
+            total_area,
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.bvh.len()
+    }
+}
+
+impl MeshTriangle {
+    fn indices(&self) -> [usize; 3] {
+        self.mesh.buffers.indices[self.index].map(|i| i as usize)
+    }
+
+    fn positions(&self) -> [Point3; 3] {
+        self.indices().map(|i| self.mesh.buffers.positions[i])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let [a, b, c] = self.positions();
+        let (t, b1, b2) = triangle::intersect(ray, a, b, c, ray_t)?;
+        let b0 = 1.0 - b1 - b2;
+
+        let buffers = &self.mesh.buffers;
+        let [i0, i1, i2] = self.indices();
+
+        let mut hit_rec = HitRecord::new(
+            ray.direction,
+            ray.at(t),
+            triangle::face_normal(a, b, c),
+            t,
+            self.mesh.material.clone(),
+        );
+
+        hit_rec = if buffers.uvs.is_empty() {
+            hit_rec.with_uv(Vec2::new(b1, b2), b - a, c - a)
+        } else {
+            let uvs = [buffers.uvs[i0], buffers.uvs[i1], buffers.uvs[i2]];
+            let uv = b0 * uvs[0] + b1 * uvs[1] + b2 * uvs[2];
+            match uv_derivatives([a, b, c], uvs) {
+                Some((dpdu, dpdv)) => hit_rec.with_uv(uv, dpdu, dpdv),
+                None => HitRecord { uv, ..hit_rec },
+            }
+        };
+
+        if !buffers.normals.is_empty() {
+            let shading_normal =
+                b0 * buffers.normals[i0] + b1 * buffers.normals[i1] + b2 * buffers.normals[i2];
+            hit_rec = hit_rec.with_shading_normal(shading_normal);
+        }
+
+        Some(hit_rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.positions();
+        triangle::padded_bounds(a, b, c)
+    }
+}
+
+// Solves for dP/du and dP/dv from the triangle's positions and texture coordinates; None when
+// the UVs are degenerate.
+fn uv_derivatives(positions: [Point3; 3], uvs: [Vec2; 3]) -> Option<(Vec3, Vec3)> {
+    let (duv02, duv12) = (uvs[0] - uvs[2], uvs[1] - uvs[2]);
+    let (dp02, dp12) = (positions[0] - positions[2], positions[1] - positions[2]);
+    let determinant = duv02.x * duv12.y - duv02.y * duv12.x;
+    if determinant.abs() < 1e-9 {
+        return None;
+    }
+
+    let inv_determinant = 1.0 / determinant;
+    let dpdu = (duv12.y * dp02 - duv02.y * dp12) * inv_determinant;
+    let dpdv = (duv02.x * dp12 - duv12.x * dp02) * inv_determinant;
+    Some((dpdu, dpdv))
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        self.bvh.hit(ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+
+    // Triangles are picked in proportion to their area, so the whole surface is sampled uniformly.
+    fn pdf_value(&self, origin: Point3, direction: Vec3, _time: f32) -> f32 {
+        match self.hit(&Ray::new(origin, direction), 0.001..INFINITY) {
+            Some(hit) => {
+                triangle::area_pdf(direction, hit.t, hit.geometric_normal, self.total_area)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: Point3, _time: f32) -> Vec3 {
+        if self.bvh.is_empty() {
+            return Vec3::X;
+        }
+
+        let (_, _, index) = self.areas.sample(f32::random());
+        let [a, b, c] = self.bvh.primitives()[index].positions();
+        triangle::random_point(a, b, c) - origin
+    }
+}
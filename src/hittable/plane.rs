@@ -0,0 +1,55 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::RangeExt,
+    vec::{Onb, Vec2, Vec3},
+};
+
+use super::{HitRecord, Hittable};
+
+// Infinite plane through `point`. UVs are distances along two axes in the plane, so textures
+// repeat every unit.
+pub struct Plane {
+    point: Point3,
+    frame: Onb,
+    material: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, material: Arc<dyn Material>) -> Plane {
+        Plane {
+            point,
+            frame: Onb::new(normal),
+            material,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let denominator = self.frame.w.dot(ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = self.frame.w.dot(self.point - ray.origin) / denominator;
+        if !ray_t.surrounds(&t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let local = self.frame.to_local(point - self.point);
+        let hit_rec = HitRecord::new(ray.direction, point, self.frame.w, t, self.material.clone())
+            .with_uv(Vec2::new(local.x, local.y), self.frame.u, self.frame.v);
+
+        Some(hit_rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::UNBOUNDED
+    }
+}
@@ -0,0 +1,87 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::{Random, RangeExt, INFINITY},
+    vec::{Vec2, Vec3},
+};
+
+use super::{
+    triangle::{area_pdf, BOUNDS_PADDING},
+    HitRecord, Hittable,
+};
+
+// Parallelogram spanned by the edges `u` and `v` from the corner `q`. The normal follows
+// u × v and the UVs run from 0 to 1 along each edge.
+pub struct Quad {
+    q: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    // u × v scaled so that dotting it with a cross product against an edge gives the
+    // coordinate along the other edge.
+    w: Vec3,
+    area: f32,
+    material: Arc<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, material: Arc<dyn Material>) -> Quad {
+        let n = u.cross(v);
+        Quad {
+            q,
+            u,
+            v,
+            normal: n.normalize_or_zero(),
+            w: n / n.length_squared(),
+            area: n.length(),
+            material,
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let denominator = self.normal.dot(ray.direction);
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = self.normal.dot(self.q - ray.origin) / denominator;
+        if !ray_t.surrounds(&t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let planar = point - self.q;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let hit_rec = HitRecord::new(ray.direction, point, self.normal, t, self.material.clone())
+            .with_uv(Vec2::new(alpha, beta), self.u, self.v);
+
+        Some(hit_rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let (q, u, v) = (self.q, self.u, self.v);
+        Aabb::from_points([q, q + u, q + v, q + u + v]).expand(BOUNDS_PADDING)
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, _time: f32) -> f32 {
+        match self.hit(&Ray::new(origin, direction), 0.001..INFINITY) {
+            Some(hit) => area_pdf(direction, hit.t, self.normal, self.area),
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: Point3, _time: f32) -> Vec3 {
+        self.q + f32::random() * self.u + f32::random() * self.v - origin
+    }
+}
@@ -1,28 +1,73 @@
-use std::{ops::Range, sync::Arc};
+use std::{
+    f32::consts::{PI, TAU},
+    ops::Range,
+    sync::Arc,
+};
 
-use crate::{material::Material, point::Point3, ray::Ray, utils::RangeExt};
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    sampling::{uniform_cone_pdf, uniform_sphere_pdf},
+    utils::{RangeExt, INFINITY},
+    vec::{Onb, Vec2, Vec3, VecExt},
+};
 
 use super::{HitRecord, Hittable};
 
 pub struct Sphere {
     center: Point3,
+    // Distance the center travels between time 0 and 1.
+    motion: Vec3,
     radius: f32,
     material: Arc<dyn Material>,
 }
 
 impl Sphere {
     pub fn new(center: Point3, radius: f32, material: Arc<dyn Material>) -> Sphere {
+        Sphere::moving(center, center, radius, material)
+    }
+
+    // Moves in a straight line from `start` at time 0 to `end` at time 1, and holds still
+    // outside that interval.
+    pub fn moving(start: Point3, end: Point3, radius: f32, material: Arc<dyn Material>) -> Sphere {
         Sphere {
-            center,
+            center: start,
+            motion: end - start,
             radius,
             material,
         }
     }
+
+    fn center(&self, time: f32) -> Point3 {
+        self.center + time.clamp(0.0, 1.0) * self.motion
+    }
+}
+
+// Latitude-longitude coordinates of a point on the unit sphere: u goes around the Y axis
+// starting at -X, v from the bottom pole to the top one.
+fn spherical_uv(p: Vec3, radius: f32) -> (Vec2, Vec3, Vec3) {
+    let theta = (-p.y).clamp(-1.0, 1.0).acos();
+    let phi = (-p.z).atan2(p.x) + PI;
+    let uv = Vec2::new(phi / TAU, theta / PI);
+
+    let dpdu = TAU * radius * Vec3::new(p.z, 0.0, -p.x);
+    let sin_theta = theta.sin();
+    let dpdv = if sin_theta > 1e-6 {
+        PI * radius * Vec3::new(-p.x * p.y / sin_theta, sin_theta, -p.y * p.z / sin_theta)
+    } else {
+        // At the poles every horizontal direction is tangent.
+        PI * radius * Vec3::X
+    };
+
+    (uv, dpdu, dpdv)
 }
 
 impl Hittable for Sphere {
     fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
-        let oc = self.center - ray.origin;
+        let center = self.center(ray.time);
+        let oc = center - ray.origin;
         let a = ray.direction.length_squared();
         let h = ray.direction.dot(oc);
         let c = oc.length_squared() - self.radius * self.radius;
@@ -43,14 +88,58 @@ impl Hittable for Sphere {
         }
 
         let point = ray.at(root);
+        let outward_normal = ((point - center) / self.radius).normalize();
+        let (uv, dpdu, dpdv) = spherical_uv(outward_normal, self.radius);
         let hit_rec = HitRecord::new(
             ray.direction,
             point,
-            ((point - self.center) / self.radius).normalize(),
+            outward_normal,
             root,
             self.material.clone(),
-        );
+        )
+        .with_uv(uv, dpdu, dpdv);
 
         Some(hit_rec)
     }
+
+    fn bounding_box(&self) -> Aabb {
+        let radius = Vec3::splat(self.radius.abs());
+        let end = self.center + self.motion;
+        Aabb::new(self.center - radius, self.center + radius)
+            .union(&Aabb::new(end - radius, end + radius))
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f32) -> f32 {
+        let ray = Ray::new(origin, direction).with_time(time);
+        if self.hit(&ray, 0.001..INFINITY).is_none() {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center(time) - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return uniform_sphere_pdf();
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        if cos_theta_max >= 1.0 {
+            // Too far away for its cone of directions to be resolved, as seen from distant
+            // points on an infinite plane.
+            return 0.0;
+        }
+        uniform_cone_pdf(cos_theta_max)
+    }
+
+    fn random(&self, origin: Point3, time: f32) -> Vec3 {
+        let direction = self.center(time) - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return Vec3::random_unit();
+        }
+
+        // Uniformly sample the cone of directions subtended by the sphere.
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        Onb::new(direction).to_world(Vec3::random_in_cone(cos_theta_max))
+    }
 }
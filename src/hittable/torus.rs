@@ -0,0 +1,213 @@
+use std::{f32::consts::TAU, f64::consts::PI, ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::RangeExt,
+    vec::{Onb, Vec2, Vec3},
+};
+
+use super::{cylinder::LocalHit, disk::circle_bounds, HitRecord, Hittable};
+
+// Ring around `axis` whose tube, of radius `minor_radius`, follows a circle of
+// `major_radius`. u goes around the axis and v around the tube, starting on the outside.
+pub struct Torus {
+    center: Point3,
+    frame: Onb,
+    major_radius: f32,
+    minor_radius: f32,
+    material: Arc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: f32,
+        minor_radius: f32,
+        material: Arc<dyn Material>,
+    ) -> Torus {
+        Torus {
+            center,
+            frame: Onb::new(axis),
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let origin = self.frame.to_local(ray.origin - self.center);
+        let direction = self.frame.to_local(ray.direction);
+        let length = direction.length();
+        let direction = direction / length;
+
+        // Start from where the ray enters the bounding sphere, so the quartic is solved close
+        // to the torus, and in f64, since its coefficients grow with the fourth power of the
+        // distance.
+        let (major, minor) = (self.major_radius as f64, self.minor_radius as f64);
+        let bound = major + minor;
+        let projection = -origin.dot(direction) as f64;
+        let origin_squared = origin.length_squared() as f64;
+        let discriminant = projection * projection - (origin_squared - bound * bound);
+        if discriminant < 0.0 {
+            return None;
+        }
+        let offset = (projection - discriminant.sqrt()).max(0.0);
+
+        let o = origin.as_dvec3() + offset * direction.as_dvec3();
+        let d = direction.as_dvec3();
+        let k = o.length_squared() + major * major - minor * minor;
+        let n = o.dot(d);
+        let four_major_squared = 4.0 * major * major;
+        let coefficients = [
+            k * k - four_major_squared * (o.x * o.x + o.y * o.y),
+            4.0 * n * k - 2.0 * four_major_squared * (o.x * d.x + o.y * d.y),
+            4.0 * n * n + 2.0 * k - four_major_squared * (d.x * d.x + d.y * d.y),
+            4.0 * n,
+            1.0,
+        ];
+
+        let t = quartic_roots(coefficients)
+            .into_iter()
+            .map(|s| ((s + offset) / length as f64) as f32)
+            .filter(|t| ray_t.surrounds(t))
+            .min_by(f32::total_cmp)?;
+
+        let p = origin + t * length * direction;
+        let ring = p.truncate().length();
+        let phi = p.y.atan2(p.x).rem_euclid(TAU);
+        let theta = p.z.atan2(ring - self.major_radius).rem_euclid(TAU);
+        let (cos_phi, sin_phi) = (phi.cos(), phi.sin());
+        let (cos_theta, sin_theta) = (theta.cos(), theta.sin());
+        let minor = self.minor_radius;
+
+        let hit = LocalHit {
+            t,
+            normal: Vec3::new(cos_theta * cos_phi, cos_theta * sin_phi, sin_theta),
+            uv: Vec2::new(phi / TAU, theta / TAU),
+            dpdu: TAU * Vec3::new(-p.y, p.x, 0.0),
+            dpdv: TAU * minor * Vec3::new(-sin_theta * cos_phi, -sin_theta * sin_phi, cos_theta),
+        };
+        Some(hit.to_world(ray, &self.frame, self.material.clone()))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        circle_bounds(self.center, self.frame.w, self.major_radius).expand(self.minor_radius)
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+// Real roots of the polynomial with `coefficients` from the constant term up, following
+// Schwarze's closed-form solution in Graphics Gems I. Each root is refined with a Newton step
+// since the closed form loses precision when roots are close together.
+fn quartic_roots(coefficients: [f64; 5]) -> Vec<f64> {
+    let [c0, c1, c2, c3, c4] = coefficients;
+    let (a, b, c, d) = (c3 / c4, c2 / c4, c1 / c4, c0 / c4);
+
+    // Substitute x = y - a/4 to remove the cubic term: y⁴ + p·y² + q·y + r = 0.
+    let a_squared = a * a;
+    let p = -3.0 / 8.0 * a_squared + b;
+    let q = a_squared * a / 8.0 - a * b / 2.0 + c;
+    let r = -3.0 / 256.0 * a_squared * a_squared + a_squared * b / 16.0 - a * c / 4.0 + d;
+
+    let mut roots = Vec::with_capacity(4);
+    if r.abs() < EPSILON {
+        // y·(y³ + p·y + q) = 0
+        roots.push(0.0);
+        roots.extend(cubic_roots([q, p, 0.0, 1.0]));
+    } else {
+        // Split into two quadratics using one root of the resolvent cubic.
+        let z = cubic_roots([r * p / 2.0 - q * q / 8.0, -r, -p / 2.0, 1.0])[0];
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if u.abs() < EPSILON {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if v.abs() < EPSILON {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if q < 0.0 { -v } else { v };
+        roots.extend(quadratic_roots([z - u, v, 1.0]));
+        roots.extend(quadratic_roots([z + u, -v, 1.0]));
+    }
+
+    let polynomial = |x: f64| (((c4 * x + c3) * x + c2) * x + c1) * x + c0;
+    let derivative = |x: f64| ((4.0 * c4 * x + 3.0 * c3) * x + 2.0 * c2) * x + c1;
+    roots
+        .into_iter()
+        .map(|y| {
+            let x = y - a / 4.0;
+            let slope = derivative(x);
+            if slope.abs() > EPSILON {
+                x - polynomial(x) / slope
+            } else {
+                x
+            }
+        })
+        .collect()
+}
+
+// Real roots of a monic cubic, at least one of which always exists.
+fn cubic_roots(coefficients: [f64; 4]) -> Vec<f64> {
+    let [c0, c1, c2, c3] = coefficients;
+    let (a, b, c) = (c2 / c3, c1 / c3, c0 / c3);
+
+    // Substitute x = y - a/3 to remove the quadratic term: y³ + 3p·y + 2q = 0.
+    let a_squared = a * a;
+    let p = (-a_squared / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * a_squared - a * b / 3.0 + c) / 2.0;
+    let p_cubed = p * p * p;
+    let discriminant = q * q + p_cubed;
+
+    let roots = if discriminant.abs() < EPSILON {
+        if q.abs() < EPSILON {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        let phi = (-q / (-p_cubed).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + PI / 3.0).cos(),
+            -t * (phi - PI / 3.0).cos(),
+        ]
+    } else {
+        let root = discriminant.sqrt();
+        vec![(root - q).cbrt() - (root + q).cbrt()]
+    };
+
+    roots.into_iter().map(|y| y - a / 3.0).collect()
+}
+
+fn quadratic_roots(coefficients: [f64; 3]) -> Vec<f64> {
+    let [c0, c1, c2] = coefficients;
+    let p = c1 / (2.0 * c2);
+    let q = c0 / c2;
+    let discriminant = p * p - q;
+
+    if discriminant.abs() < EPSILON {
+        vec![-p]
+    } else if discriminant < 0.0 {
+        Vec::new()
+    } else {
+        let root = discriminant.sqrt();
+        vec![root - p, -root - p]
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+use glam::{Affine3A, Mat3A, Quat};
+
+use crate::{aabb::Aabb, point::Point3, ray::Ray, vec::Vec3};
+
+use super::{HitRecord, Hittable};
+
+// Places `object` in the world through an affine transform. Rays are taken into object space,
+// so wrapping an `Arc` instances the same geometry any number of times.
+pub struct Transformed<H: Hittable> {
+    object: H,
+    placement: Placement,
+    motion: Option<Motion>,
+    bbox: Aabb,
+}
+
+impl<H: Hittable> Transformed<H> {
+    pub fn new(object: H, transform: Affine3A) -> Transformed<H> {
+        let bbox = transform_box(&object.bounding_box(), &transform);
+
+        Transformed {
+            object,
+            placement: Placement::new(transform),
+            motion: None,
+            bbox,
+        }
+    }
+
+    // Moves from `start` at time 0 to `end` at time 1, interpolating scale, rotation and
+    // translation separately, and holds still outside that interval. Both transforms must be
+    // made of just those three parts, which `is_decomposable` checks.
+    pub fn moving(object: H, start: Affine3A, end: Affine3A) -> Transformed<H> {
+        debug_assert!(is_decomposable(&start) && is_decomposable(&end));
+        let motion = Motion {
+            start: start.to_scale_rotation_translation(),
+            end: end.to_scale_rotation_translation(),
+        };
+        let bbox = motion.bounds(&object.bounding_box());
+
+        Transformed {
+            object,
+            placement: Placement::new(start),
+            motion: Some(motion),
+            bbox,
+        }
+    }
+
+    fn placement(&self, time: f32) -> Placement {
+        match &self.motion {
+            Some(motion) => Placement::new(motion.at(time)),
+            None => self.placement,
+        }
+    }
+}
+
+// Whether `transform` is a translation of a rotation of an axis-aligned scale, with no shear
+// that splitting it into those parts would lose.
+pub fn is_decomposable(transform: &Affine3A) -> bool {
+    let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+    let recomposed = Affine3A::from_scale_rotation_translation(scale, rotation, translation);
+    let size = [transform.x_axis, transform.y_axis, transform.z_axis]
+        .map(|axis| axis.length())
+        .into_iter()
+        .fold(0.0, f32::max);
+    recomposed.abs_diff_eq(*transform, 1e-4 * size)
+}
+
+#[derive(Clone, Copy)]
+struct Placement {
+    transform: Affine3A,
+    inverse: Affine3A,
+    // Inverse transpose of the linear part, which keeps normals perpendicular to the surface.
+    normal_matrix: Mat3A,
+}
+
+impl Placement {
+    fn new(transform: Affine3A) -> Placement {
+        let inverse = transform.inverse();
+        Placement {
+            transform,
+            inverse,
+            normal_matrix: inverse.matrix3.transpose(),
+        }
+    }
+}
+
+// Scale, rotation and translation at time 0 and 1.
+struct Motion {
+    start: (glam::Vec3, Quat, glam::Vec3),
+    end: (glam::Vec3, Quat, glam::Vec3),
+}
+
+impl Motion {
+    fn at(&self, time: f32) -> Affine3A {
+        let time = time.clamp(0.0, 1.0);
+        let (start_scale, start_rotation, start_translation) = self.start;
+        let (end_scale, end_rotation, end_translation) = self.end;
+        Affine3A::from_scale_rotation_translation(
+            start_scale.lerp(end_scale, time),
+            start_rotation.slerp(end_rotation, time),
+            start_translation.lerp(end_translation, time),
+        )
+    }
+
+    fn bounds(&self, bbox: &Aabb) -> Aabb {
+        if bbox.is_empty() || bbox.is_unbounded() {
+            return *bbox;
+        }
+
+        let start = transform_box(bbox, &self.at(0.0));
+        let end = transform_box(bbox, &self.at(1.0));
+        if self.start.1.dot(self.end.1).abs() >= 1.0 - 1e-6 {
+            // Without rotation every corner moves in a straight line.
+            return start.union(&end);
+        }
+
+        // A rotating object stays within the largest distance its corners reach from its
+        // origin, around the straight path the origin takes.
+        let scale = self.start.0.abs().max(self.end.0.abs());
+        let reach = corners(bbox)
+            .map(|corner| (Vec3::from(scale) * corner).length())
+            .fold(0.0, f32::max);
+        let path = Aabb::new(self.start.2.into(), self.end.2.into());
+        start.union(&end).union(&path.expand(reach))
+    }
+}
+
+fn corners(bbox: &Aabb) -> impl Iterator<Item = Point3> + '_ {
+    (0..8).map(|i| {
+        Vec3::new(
+            if i & 1 == 0 { bbox.min.x } else { bbox.max.x },
+            if i & 2 == 0 { bbox.min.y } else { bbox.max.y },
+            if i & 4 == 0 { bbox.min.z } else { bbox.max.z },
+        )
+    })
+}
+
+fn transform_box(bbox: &Aabb, transform: &Affine3A) -> Aabb {
+    if bbox.is_empty() || bbox.is_unbounded() {
+        return *bbox;
+    }
+
+    Aabb::from_points(corners(bbox).map(|corner| transform.transform_point3a(corner)))
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let placement = self.placement(ray.time);
+
+        // The direction is left unnormalized so distances along both rays agree.
+        let local = ray.spawn(
+            placement.inverse.transform_point3a(ray.origin),
+            placement.inverse.transform_vector3a(ray.direction),
+        );
+        let hit = self.object.hit(&local, ray_t)?;
+
+        // The inverse transpose preserves which side of the surface the ray is on, so
+        // `front_face` and the normal orientation carry over.
+        Some(HitRecord {
+            point: placement.transform.transform_point3a(hit.point),
+            normal: (placement.normal_matrix * hit.normal).normalize(),
+            geometric_normal: (placement.normal_matrix * hit.geometric_normal).normalize(),
+            dpdu: placement.transform.transform_vector3a(hit.dpdu),
+            dpdv: placement.transform.transform_vector3a(hit.dpdv),
+            ..hit
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, time: f32) -> f32 {
+        let placement = self.placement(time);
+        let Some(local) = placement
+            .inverse
+            .transform_vector3a(direction)
+            .try_normalize()
+        else {
+            return 0.0;
+        };
+        let pdf = self
+            .object
+            .pdf_value(placement.inverse.transform_point3a(origin), local, time);
+
+        // Mapping unit directions through a linear map M stretches solid angle by
+        // |det M| / |M d|^3.
+        let stretch = placement.transform.transform_vector3a(local).length();
+        pdf * stretch.powi(3) / placement.transform.matrix3.determinant().abs()
+    }
+
+    fn random(&self, origin: Point3, time: f32) -> Vec3 {
+        let placement = self.placement(time);
+        let local = self
+            .object
+            .random(placement.inverse.transform_point3a(origin), time);
+        placement.transform.transform_vector3a(local)
+    }
+}
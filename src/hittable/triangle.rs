@@ -0,0 +1,132 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    point::Point3,
+    ray::Ray,
+    utils::{Random, RangeExt, INFINITY},
+    vec::{Vec2, Vec3},
+};
+
+use super::{HitRecord, Hittable};
+
+pub(super) const BOUNDS_PADDING: f32 = 1e-4;
+
+pub struct Triangle {
+    vertices: [Point3; 3],
+    material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(a: Point3, b: Point3, c: Point3, material: Arc<dyn Material>) -> Triangle {
+        Triangle {
+            vertices: [a, b, c],
+            material,
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, ray_t: Range<f32>) -> Option<HitRecord> {
+        let [a, b, c] = self.vertices;
+        let (t, b1, b2) = intersect(ray, a, b, c, ray_t)?;
+
+        let hit_rec = HitRecord::new(
+            ray.direction,
+            ray.at(t),
+            face_normal(a, b, c),
+            t,
+            self.material.clone(),
+        )
+        .with_uv(Vec2::new(b1, b2), b - a, c - a);
+
+        Some(hit_rec)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.vertices;
+        padded_bounds(a, b, c)
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3, _time: f32) -> f32 {
+        let [a, b, c] = self.vertices;
+        match self.hit(&Ray::new(origin, direction), 0.001..INFINITY) {
+            Some(hit) => area_pdf(direction, hit.t, hit.geometric_normal, area(a, b, c)),
+            None => 0.0,
+        }
+    }
+
+    fn random(&self, origin: Point3, _time: f32) -> Vec3 {
+        let [a, b, c] = self.vertices;
+        random_point(a, b, c) - origin
+    }
+}
+
+// Möller–Trumbore; returns the ray parameter and the barycentrics of `b` and `c`.
+pub(super) fn intersect(
+    ray: &Ray,
+    a: Point3,
+    b: Point3,
+    c: Point3,
+    ray_t: Range<f32>,
+) -> Option<(f32, f32, f32)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+
+    let p = ray.direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    // Relative to the sizes involved, so small triangles and scaled instances aren't lost.
+    let scale = edge1.length() * edge2.length() * ray.direction.length();
+    if determinant.abs() <= f32::EPSILON * scale {
+        return None;
+    }
+    let inv_determinant = 1.0 / determinant;
+
+    let s = ray.origin - a;
+    let b1 = s.dot(p) * inv_determinant;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let b2 = ray.direction.dot(q) * inv_determinant;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inv_determinant;
+    if !ray_t.surrounds(&t) {
+        return None;
+    }
+
+    Some((t, b1, b2))
+}
+
+pub(super) fn padded_bounds(a: Point3, b: Point3, c: Point3) -> Aabb {
+    Aabb::from_points([a, b, c]).expand(BOUNDS_PADDING)
+}
+
+pub(super) fn face_normal(a: Point3, b: Point3, c: Point3) -> Vec3 {
+    (b - a).cross(c - a).normalize()
+}
+
+pub(super) fn area(a: Point3, b: Point3, c: Point3) -> f32 {
+    0.5 * (b - a).cross(c - a).length()
+}
+
+pub(super) fn random_point(a: Point3, b: Point3, c: Point3) -> Point3 {
+    let su = f32::random().sqrt();
+    let (b0, b1) = (1.0 - su, f32::random() * su);
+    b0 * a + b1 * b + (1.0 - b0 - b1) * c
+}
+
+// Converts a uniform density over `area` into a solid-angle density seen along `direction`.
+pub(super) fn area_pdf(direction: Vec3, t: f32, normal: Vec3, area: f32) -> f32 {
+    let distance_squared = t * t * direction.length_squared();
+    let cosine = (direction.dot(normal) / direction.length()).abs();
+    if cosine <= 0.0 || area <= 0.0 || !distance_squared.is_finite() {
+        return 0.0;
+    }
+    distance_squared / (cosine * area)
+}
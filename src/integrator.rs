@@ -0,0 +1,140 @@
+pub mod ambient_occlusion;
+pub mod debug;
+pub mod direct_lighting;
+pub mod naive;
+pub mod path;
+
+use std::sync::Arc;
+
+use crate::{
+    background::Background,
+    color::{Color, Linear},
+    hittable::{constant_medium::Fog, HitRecord, Hittable},
+    ray::Ray,
+    sampling::power_heuristic,
+    utils::{Random, INFINITY},
+    vec::Vec3,
+};
+
+pub struct RenderContext<'a> {
+    pub world: &'a dyn Hittable,
+    pub lights: &'a dyn Hittable,
+    pub background: &'a dyn Background,
+    // Medium filling the space between surfaces, if any.
+    pub fog: Option<&'a Fog>,
+    pub max_depth: u32,
+}
+
+impl RenderContext<'_> {
+    // Closest surface along `ray`, with its shading normal adjusted by the material, or the
+    // point where the fog scatters the ray first. Fog only fills the space up to the surface,
+    // so rays escaping to the background leave it after its `max_distance`.
+    pub fn hit(&self, ray: &Ray) -> Option<HitRecord> {
+        let hit = self.world.hit(ray, 0.001..INFINITY);
+        if let Some(fog) = self.fog {
+            let end = hit.as_ref().map_or(INFINITY, |hit| hit.t);
+            if let Some(scattered) = fog.hit(ray, 0.001..end) {
+                return Some(scattered);
+            }
+        }
+
+        let hit = hit?;
+        match hit.material.shading_normal(&hit) {
+            Some(normal) => Some(hit.with_shading_normal(normal)),
+            None => Some(hit),
+        }
+    }
+}
+
+pub trait Integrator: Send + Sync + std::fmt::Debug {
+    // Radiance arriving at the origin of `ray` from its direction.
+    fn li(&self, ray: &Ray, context: &RenderContext) -> Color<Linear>;
+}
+
+// Integrators that need no parameters, by the names the scene format and CLI use.
+pub fn by_name(name: &str) -> Option<Arc<dyn Integrator>> {
+    let integrator: Arc<dyn Integrator> = match name {
+        "path" => Arc::new(path::PathTracer),
+        "naive" => Arc::new(naive::NaivePathTracer),
+        "ao" | "ambient_occlusion" => Arc::new(ambient_occlusion::AmbientOcclusion::default()),
+        "direct" | "direct_lighting" => Arc::new(direct_lighting::DirectLighting),
+        "normals" => Arc::new(debug::DebugView::Normals),
+        "albedo" => Arc::new(debug::DebugView::Albedo),
+        _ => return None,
+    };
+    Some(integrator)
+}
+
+// Bounces every path takes before Russian roulette may end it.
+const ROULETTE_MIN_BOUNCES: u32 = 3;
+
+// Randomly ends paths whose throughput has become small, reweighting survivors so the estimate
+// stays unbiased. Returns false when the path should stop.
+fn russian_roulette(throughput: &mut Vec3, bounce: u32) -> bool {
+    if bounce < ROULETTE_MIN_BOUNCES {
+        return true;
+    }
+
+    let survival = throughput.max_element().min(0.95);
+    if survival <= 0.0 || f32::random() >= survival {
+        return false;
+    }
+    *throughput /= survival;
+    true
+}
+
+// Light- and environment-sampled direct lighting at a non-specular hit, MIS-weighted against
+// the material's own sampling.
+fn sample_direct(ray: &Ray, hit: &HitRecord, context: &RenderContext) -> Vec3 {
+    let mut direct = Vec3::ZERO;
+
+    let direction = context.lights.random(hit.point, ray.time);
+    let light_pdf = context.lights.pdf_value(hit.point, direction, ray.time);
+    if light_pdf > 0.0 {
+        let shadow_ray = ray.spawn(hit.point, direction);
+        if let Some(f_cos) = bsdf_cos(ray, hit, direction) {
+            if let Some(light_hit) = context.hit(&shadow_ray) {
+                let emitted = light_hit.material.emitted(&shadow_ray, &light_hit);
+                let bsdf_pdf = hit.material.pdf(ray, hit, direction);
+                let weight = power_heuristic(light_pdf, bsdf_pdf);
+                direct += weight * f_cos * emitted.v / light_pdf;
+            }
+        }
+    }
+
+    if let Some(sample) = context.background.sample() {
+        let shadow_ray = ray.spawn(hit.point, sample.direction);
+        if let Some(f_cos) = bsdf_cos(ray, hit, sample.direction) {
+            if context.hit(&shadow_ray).is_none() {
+                let bsdf_pdf = hit.material.pdf(ray, hit, sample.direction);
+                let weight = power_heuristic(sample.pdf, bsdf_pdf);
+                direct += weight * f_cos * sample.color.v / sample.pdf;
+            }
+        }
+    }
+
+    direct
+}
+
+// BSDF times cosine for scattering `ray` into `direction`, or None if nothing is scattered.
+fn bsdf_cos(ray: &Ray, hit: &HitRecord, direction: Vec3) -> Option<Vec3> {
+    let f = hit.material.eval(ray, hit, direction).v;
+    let f_cos = f * hit.cosine(direction);
+    (f_cos.max_element() > 0.0).then_some(f_cos)
+}
+
+// Emission reached by a material-sampled ray whose origin was also light sampled.
+fn weighted_emission(ray: &Ray, bsdf_pdf: f32, context: &RenderContext) -> Vec3 {
+    match context.hit(ray) {
+        Some(hit) => {
+            let light_pdf = context
+                .lights
+                .pdf_value(ray.origin(), ray.direction(), ray.time);
+            power_heuristic(bsdf_pdf, light_pdf) * hit.material.emitted(ray, &hit).v
+        }
+        None => {
+            let background_pdf = context.background.pdf(ray.direction());
+            power_heuristic(bsdf_pdf, background_pdf) * context.background.color(ray).v
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    utils::INFINITY,
+    vec::{Onb, Vec3, VecExt},
+};
+
+use super::{Integrator, RenderContext};
+
+// Fraction of the hemisphere above the first hit that is unoccluded within `distance`,
+// cosine weighted. Rays that escape the scene count as fully open.
+#[derive(Clone, Copy, Debug)]
+pub struct AmbientOcclusion {
+    pub distance: f32,
+}
+
+impl AmbientOcclusion {
+    pub fn new(distance: f32) -> Self {
+        Self { distance }
+    }
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        Self { distance: INFINITY }
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn li(&self, ray: &Ray, context: &RenderContext) -> Color<Linear> {
+        let Some(hit) = context.hit(ray) else {
+            return Color::from(Vec3::ONE);
+        };
+
+        let direction = Onb::new(hit.normal).to_world(Vec3::random_cosine_direction());
+        let occlusion_ray = ray.spawn(hit.point, direction);
+        let open = context
+            .world
+            .hit(&occlusion_ray, 0.001..self.distance)
+            .is_none();
+        Color::from(if open { Vec3::ONE } else { Vec3::ZERO })
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    vec::Vec3,
+};
+
+use super::{Integrator, RenderContext};
+
+// Visualizes surface data at the first hit instead of computing lighting.
+#[derive(Clone, Copy, Debug)]
+pub enum DebugView {
+    // World-space shading normal mapped from [-1, 1] to [0, 1].
+    Normals,
+    // Material reflectance, or emitted radiance for lights.
+    Albedo,
+}
+
+impl Integrator for DebugView {
+    fn li(&self, ray: &Ray, context: &RenderContext) -> Color<Linear> {
+        let Some(hit) = context.hit(ray) else {
+            return match self {
+                DebugView::Normals => Color::from(Vec3::ZERO),
+                DebugView::Albedo => context.background.color(ray),
+            };
+        };
+
+        match self {
+            DebugView::Normals => Color::from(0.5 * (hit.normal + Vec3::ONE)),
+            DebugView::Albedo => match hit.material.scatter(ray, &hit) {
+                Some(scatter) => Color::from(scatter.weight(&hit)),
+                None => hit.material.emitted(ray, &hit),
+            },
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    vec::Vec3,
+};
+
+use super::{sample_direct, weighted_emission, Integrator, RenderContext};
+
+// Single-bounce lighting: specular surfaces are followed until the first diffuse hit, which is
+// lit by emitters and the environment only, with no interreflection.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DirectLighting;
+
+impl Integrator for DirectLighting {
+    fn li(&self, ray: &Ray, context: &RenderContext) -> Color<Linear> {
+        let mut ray = ray.clone();
+        let mut throughput = Vec3::ONE;
+        let mut radiance = Vec3::ZERO;
+
+        for _ in 0..context.max_depth {
+            let Some(hit) = context.hit(&ray) else {
+                radiance += throughput * context.background.color(&ray).v;
+                return Color::from(radiance);
+            };
+
+            radiance += throughput * hit.material.emitted(&ray, &hit).v;
+            let Some(scatter) = hit.material.scatter(&ray, &hit) else {
+                return Color::from(radiance);
+            };
+
+            let scattered = scatter.ray(&ray, hit.point);
+            if scatter.specular {
+                throughput *= scatter.weight(&hit);
+                ray = scattered;
+                continue;
+            }
+
+            let direct = sample_direct(&ray, &hit, context);
+            let indirect = weighted_emission(&scattered, scatter.pdf, context);
+            radiance += throughput * (direct + scatter.weight(&hit) * indirect);
+            return Color::from(radiance);
+        }
+
+        Color::from(radiance)
+    }
+}
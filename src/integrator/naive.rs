@@ -0,0 +1,40 @@
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    vec::Vec3,
+};
+
+use super::{russian_roulette, Integrator, RenderContext};
+
+// Pure random walk that only finds lights by hitting them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NaivePathTracer;
+
+impl Integrator for NaivePathTracer {
+    fn li(&self, ray: &Ray, context: &RenderContext) -> Color<Linear> {
+        let mut ray = ray.clone();
+        let mut throughput = Vec3::ONE;
+        let mut radiance = Vec3::ZERO;
+
+        for bounce in 0..context.max_depth {
+            let Some(hit) = context.hit(&ray) else {
+                radiance += throughput * context.background.color(&ray).v;
+                break;
+            };
+
+            radiance += throughput * hit.material.emitted(&ray, &hit).v;
+            let Some(scatter) = hit.material.scatter(&ray, &hit) else {
+                break;
+            };
+
+            throughput *= scatter.weight(&hit);
+            ray = scatter.ray(&ray, hit.point);
+
+            if !russian_roulette(&mut throughput, bounce) {
+                break;
+            }
+        }
+
+        Color::from(radiance)
+    }
+}
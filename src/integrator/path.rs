@@ -0,0 +1,65 @@
+use crate::{
+    color::{Color, Linear},
+    ray::Ray,
+    sampling::power_heuristic,
+    vec::Vec3,
+};
+
+use super::{russian_roulette, sample_direct, Integrator, RenderContext};
+
+// Path tracer with next-event estimation: lights and the environment are sampled explicitly
+// at every diffuse bounce and combined with material sampling through MIS.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PathTracer;
+
+impl Integrator for PathTracer {
+    fn li(&self, ray: &Ray, context: &RenderContext) -> Color<Linear> {
+        let mut ray = ray.clone();
+        let mut throughput = Vec3::ONE;
+        let mut radiance = Vec3::ZERO;
+        // Density the previous bounce sampled `ray` with, when that bounce also sampled the
+        // lights directly and emission found along `ray` has to be weighted against it.
+        let mut bsdf_pdf: Option<f32> = None;
+
+        for bounce in 0..context.max_depth {
+            let Some(hit) = context.hit(&ray) else {
+                let weight = bsdf_pdf.map_or(1.0, |pdf| {
+                    power_heuristic(pdf, context.background.pdf(ray.direction()))
+                });
+                radiance += weight * throughput * context.background.color(&ray).v;
+                break;
+            };
+
+            let weight = bsdf_pdf.map_or(1.0, |pdf| {
+                let light_pdf = context
+                    .lights
+                    .pdf_value(ray.origin(), ray.direction(), ray.time);
+                power_heuristic(pdf, light_pdf)
+            });
+            radiance += weight * throughput * hit.material.emitted(&ray, &hit).v;
+
+            let Some(scatter) = hit.material.scatter(&ray, &hit) else {
+                break;
+            };
+
+            if scatter.specular {
+                bsdf_pdf = None;
+            } else {
+                // Light found on the last bounce could not be matched by a material sample.
+                if bounce + 1 < context.max_depth {
+                    radiance += throughput * sample_direct(&ray, &hit, context);
+                }
+                bsdf_pdf = Some(scatter.pdf);
+            }
+
+            throughput *= scatter.weight(&hit);
+            ray = scatter.ray(&ray, hit.point);
+
+            if !russian_roulette(&mut throughput, bounce) {
+                break;
+            }
+        }
+
+        Color::from(radiance)
+    }
+}
@@ -1,27 +1,82 @@
-use std::path::PathBuf;
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
 
 use camera::CameraConfig;
-use hittable::Hittable;
-use image::ImageResult;
+use color::{Color, Linear};
+use hittable::{Hittable, LightList};
+use image::{
+    codecs::pnm::{PnmEncoder, PnmSubtype, SampleEncoding},
+    ImageFormat, ImageResult, Rgb32FImage, RgbImage,
+};
+use vec::Vec3;
 
+pub mod aabb;
+pub mod background;
 pub mod camera;
 pub mod color;
 pub mod hittable;
+pub mod integrator;
 pub mod material;
+pub mod obj;
 pub mod point;
 pub mod ray;
+pub mod sampling;
+pub mod scene;
+pub mod spectrum;
+pub mod texture;
 pub mod utils;
 pub mod vec;
 
 pub mod prelude {
     pub use crate::{
+        aabb::Aabb,
+        background::{
+            environment_map::EnvironmentMap, Background, Black, Gradient, PhysicalSky, Solid,
+        },
         camera::CameraConfig,
         color::Color,
-        hittable::{sphere::Sphere, Hittable, HittableList},
-        material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal, Material},
+        hittable::{
+            bvh::{Bvh, SplitMethod},
+            cone::Cone,
+            constant_medium::{ConstantMedium, Fog},
+            cuboid::Cuboid,
+            cylinder::Cylinder,
+            disk::Disk,
+            mesh::{MeshBuffers, TriangleMesh},
+            plane::Plane,
+            quad::Quad,
+            sphere::Sphere,
+            torus::Torus,
+            transformed::Transformed,
+            triangle::Triangle,
+            Hittable, HittableList, LightList,
+        },
+        integrator::{
+            ambient_occlusion::AmbientOcclusion, debug::DebugView, direct_lighting::DirectLighting,
+            naive::NaivePathTracer, path::PathTracer, Integrator,
+        },
+        material::{
+            dielectric::{Dielectric, Dispersion},
+            diffuse_light::DiffuseLight,
+            henyey_greenstein::HenyeyGreenstein,
+            isotropic::Isotropic,
+            lambertian::Lambertian,
+            metal::Metal,
+            microfacet::{Fresnel, Microfacet},
+            normal_map::{NormalMapped, NormalMapping},
+            principled::Principled,
+            Material, ScatterRecord,
+        },
         point::Point3,
         render,
-        vec::Vec3,
+        scene::Scene,
+        texture::{
+            checker::Checker, image::ImageTexture, noise::Noise, solid::SolidColor, Texture,
+        },
+        vec::{Vec2, Vec3, VecExt},
     };
 }
 
@@ -29,16 +84,72 @@ pub fn render(
     world: &impl Hittable,
     path: impl Into<PathBuf>,
     camera_config: &CameraConfig,
+) -> ImageResult<()> {
+    let path: PathBuf = path.into();
+    let format = output_format(&path)?;
+    render_with_format(world, path, format, camera_config)
+}
+
+pub fn render_with_format(
+    world: &impl Hittable,
+    path: impl Into<PathBuf>,
+    format: ImageFormat,
+    camera_config: &CameraConfig,
 ) -> ImageResult<()> {
     let camera = camera::Camera::new(camera_config);
-    let image = camera.render(world);
+    let image = camera.render(world, &LightList::new());
+    save(image, path.into(), format)
+}
 
-    let path: PathBuf = path.into();
+pub(crate) fn output_format(path: &Path) -> ImageResult<ImageFormat> {
+    if path.extension().is_some() && !path.is_dir() {
+        ImageFormat::from_path(path)
+    } else {
+        Ok(ImageFormat::Png)
+    }
+}
+
+// Extension for files written in `format`. The first PNM extension is for bitmaps, but color
+// images are pixmaps.
+pub fn default_extension(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Pnm => "ppm",
+        _ => format.extensions_str()[0],
+    }
+}
+
+// Writes linear radiance as is to HDR formats, and sRGB-encoded in 8 bits to the rest.
+pub(crate) fn save(image: Rgb32FImage, path: PathBuf, format: ImageFormat) -> ImageResult<()> {
+    let extension = default_extension(format);
     let path = if path.is_dir() {
-        path.join("image.png")
+        path.join("image").with_extension(extension)
+    } else if path.extension().is_none() {
+        path.with_extension(extension)
     } else {
-        path.with_extension("png")
+        path
     };
 
-    image.save(path)
+    if matches!(format, ImageFormat::Hdr | ImageFormat::OpenExr) {
+        return image.save_with_format(path, format);
+    }
+
+    let image = RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let color: Color<Linear> = Color::from(Vec3::from_array(image.get_pixel(x, y).0));
+        color.to_srgb().into()
+    });
+    if format == ImageFormat::Pnm {
+        return save_pnm(&image, &path);
+    }
+    image.save_with_format(path, format)
+}
+
+// Binary PPM, unless the path asks for PAM; `save_with_format` always writes PAM.
+fn save_pnm(image: &RgbImage, path: &Path) -> ImageResult<()> {
+    let subtype = if path.extension().is_some_and(|extension| extension == "pam") {
+        PnmSubtype::ArbitraryMap
+    } else {
+        PnmSubtype::Pixmap(SampleEncoding::Binary)
+    };
+    let file = BufWriter::new(File::create(path)?);
+    image.write_with_encoder(PnmEncoder::new(file).with_subtype(subtype))
 }
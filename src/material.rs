@@ -1,13 +1,103 @@
 pub mod dielectric;
+pub mod diffuse_light;
+mod ggx;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod microfacet;
+pub mod normal_map;
+pub mod principled;
 
 use crate::{
     color::{Color, Linear},
     hittable::HitRecord,
+    point::Point3,
     ray::Ray,
+    vec::Vec3,
 };
 
+// One direction sampled by `Material::scatter`.
+#[derive(Clone, Copy, Debug)]
+pub struct ScatterRecord {
+    pub direction: Vec3,
+    // BSDF value for `direction`, or the whole path weight for specular samples.
+    pub value: Color<Linear>,
+    // Solid-angle density `direction` was chosen with; meaningless for specular samples.
+    pub pdf: f32,
+    // Sampled from a delta distribution that `eval` and `pdf` can't reproduce.
+    pub specular: bool,
+    // Wavelength the rest of the path is restricted to, when this scatter picked one.
+    pub wavelength: Option<f32>,
+}
+
+impl ScatterRecord {
+    pub fn new(direction: Vec3, value: Color<Linear>, pdf: f32) -> Self {
+        Self {
+            direction,
+            value,
+            pdf,
+            specular: false,
+            wavelength: None,
+        }
+    }
+
+    pub fn specular(direction: Vec3, weight: Color<Linear>) -> Self {
+        Self {
+            direction,
+            value: weight,
+            pdf: 0.0,
+            specular: true,
+            wavelength: None,
+        }
+    }
+
+    pub fn with_wavelength(self, wavelength: f32) -> Self {
+        Self {
+            wavelength: Some(wavelength),
+            ..self
+        }
+    }
+
+    // The ray continuing the path of `ray` from `origin` in the sampled direction.
+    pub fn ray(&self, ray: &Ray, origin: Point3) -> Ray {
+        let mut scattered = ray.spawn(origin, self.direction);
+        scattered.wavelength = self.wavelength.or(ray.wavelength);
+        scattered
+    }
+
+    // Factor the path throughput is multiplied by: f * |cos| / pdf.
+    pub fn weight(&self, hit: &HitRecord) -> Vec3 {
+        if self.specular {
+            self.value.v
+        } else if self.pdf > 0.0 {
+            self.value.v * hit.cosine(self.direction) / self.pdf
+        } else {
+            Vec3::ZERO
+        }
+    }
+}
+
+// Directions are world space: `ray` arrives at `hit` and `direction` leaves it.
 pub trait Material: Send + Sync + std::fmt::Debug {
-    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color<Linear>)>;
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord>;
+
+    // BSDF value for scattering `ray` into `direction`, without the cosine term.
+    fn eval(&self, _ray: &Ray, _hit: &HitRecord, _direction: Vec3) -> Color<Linear> {
+        Color::from(Vec3::ZERO)
+    }
+
+    // Density of `scatter` choosing `direction`; 0 for purely specular materials.
+    fn pdf(&self, _ray: &Ray, _hit: &HitRecord, _direction: Vec3) -> f32 {
+        0.0
+    }
+
+    fn emitted(&self, _ray: &Ray, _hit: &HitRecord) -> Color<Linear> {
+        Color::from(Vec3::ZERO)
+    }
+
+    // Replacement shading normal at `hit`, applied before any of the methods above run.
+    fn shading_normal(&self, _hit: &HitRecord) -> Option<Vec3> {
+        None
+    }
 }
@@ -1,15 +1,101 @@
-use crate::{color::Color, hittable::HitRecord, ray::Ray, utils::Random, vec::Vec3};
+use crate::{
+    color::{Color, Linear},
+    hittable::HitRecord,
+    ray::Ray,
+    spectrum::{sample_wavelength, wavelength_response},
+    utils::Random,
+    vec::{Onb, Vec3},
+};
 
-use super::{Linear, Material};
+use super::{ggx, Material, ScatterRecord};
+
+// Wavelength used for the index of refraction of rays that don't carry one.
+const REFERENCE_WAVELENGTH: f32 = 550.0;
+
+// Index of refraction as a function of wavelength, with wavelengths in micrometers.
+#[derive(Clone, Copy, Debug)]
+pub enum Dispersion {
+    // n = a + b / λ²
+    Cauchy { a: f32, b: f32 },
+    // n² = 1 + Σ bᵢ λ² / (λ² - cᵢ)
+    Sellmeier { b: [f32; 3], c: [f32; 3] },
+}
+
+impl Dispersion {
+    pub const BK7: Dispersion = Dispersion::Sellmeier {
+        b: [1.039_612, 0.231_792_3, 1.010_469],
+        c: [0.006_000_7, 0.020_017_9, 103.560_65],
+    };
+
+    pub const DIAMOND: Dispersion = Dispersion::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030_625, 0.011_236, 0.0],
+    };
+
+    // Index of refraction at a wavelength given in nanometers.
+    pub fn ior(&self, wavelength: f32) -> f32 {
+        let lambda2 = (wavelength * 1e-3).powi(2);
+        match *self {
+            Dispersion::Cauchy { a, b } => a + b / lambda2,
+            Dispersion::Sellmeier { b, c } => {
+                let sum: f32 = (0..3).map(|i| b[i] * lambda2 / (lambda2 - c[i])).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Dielectric {
     refraction_index: f32,
+    dispersion: Option<Dispersion>,
+    // Beer-Lambert absorption coefficient per unit distance travelled inside.
+    absorption: Vec3,
+    alpha: f32,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f32) -> Dielectric {
-        Dielectric { refraction_index }
+        Dielectric {
+            refraction_index,
+            dispersion: None,
+            absorption: Vec3::ZERO,
+            alpha: 0.0,
+        }
+    }
+
+    // Makes the index of refraction depend on wavelength, replacing the constant one.
+    pub fn with_dispersion(self, dispersion: Dispersion) -> Self {
+        Self {
+            refraction_index: dispersion.ior(REFERENCE_WAVELENGTH),
+            dispersion: Some(dispersion),
+            ..self
+        }
+    }
+
+    pub fn with_absorption(self, absorption: Color<Linear>) -> Self {
+        Self {
+            absorption: absorption.v.max(Vec3::ZERO),
+            ..self
+        }
+    }
+
+    // Absorbs so that light keeps `color` of its energy after travelling `distance` inside.
+    pub fn with_tint(self, color: Color<Linear>, distance: f32) -> Self {
+        let color = color.v.clamp(Vec3::splat(1e-6), Vec3::ONE);
+        let absorption = -Vec3::from(color.to_array().map(f32::ln)) / distance;
+        Self {
+            absorption: absorption.max(Vec3::ZERO),
+            ..self
+        }
+    }
+
+    // Frosts the surface with a GGX microfacet distribution; 0 keeps it perfectly smooth.
+    pub fn with_roughness(self, roughness: f32) -> Self {
+        Self {
+            alpha: ggx::alpha_from_roughness(roughness),
+            ..self
+        }
     }
 
     fn reflectance(&self, cosine: f32, ref_idx: f32) -> f32 {
@@ -17,15 +103,64 @@ impl Dielectric {
         let r0 = r0 * r0;
         r0 + (1.0 - r0) * (1.0 - cosine).powf(5.0)
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color<Linear>)> {
-        let ri = if hit.front_face {
-            1.0 / self.refraction_index
-        } else {
-            self.refraction_index
+    fn is_smooth(&self) -> bool {
+        self.alpha < ggx::SPECULAR_ALPHA
+    }
+
+    // Relative index of refraction across the surface for a ray arriving on its `hit` side.
+    fn eta(&self, hit: &HitRecord, wavelength: Option<f32>) -> f32 {
+        let ior = match (self.dispersion, wavelength) {
+            (Some(dispersion), Some(wavelength)) => dispersion.ior(wavelength),
+            _ => self.refraction_index,
         };
+        if hit.front_face {
+            ior
+        } else {
+            1.0 / ior
+        }
+    }
+
+    // Dispersive glass can only be evaluated once the path has settled on a wavelength.
+    fn needs_wavelength(&self, ray: &Ray) -> bool {
+        self.dispersion.is_some() && ray.wavelength.is_none()
+    }
+
+    // A ray reaching the inside of the surface has travelled through the medium to get there.
+    fn transmittance(&self, ray: &Ray, hit: &HitRecord) -> Vec3 {
+        if hit.front_face || self.absorption == Vec3::ZERO {
+            return Vec3::ONE;
+        }
+        let distance = hit.t * ray.direction().length();
+        (-self.absorption * distance).exp()
+    }
+
+    fn scatter_at(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        wavelength: Option<f32>,
+    ) -> Option<ScatterRecord> {
+        let transmittance = self.transmittance(ray, hit);
+        let eta = self.eta(hit, wavelength);
+
+        if !self.is_smooth() {
+            let frame = Onb::new(hit.normal);
+            let wo = frame.to_local(-ray.direction().normalize());
+            let u = [f32::random(), f32::random(), f32::random()];
+            let wi = ggx::sample_dielectric(wo, eta, self.alpha, u)?;
+            let (value, pdf) = ggx::dielectric(wo, wi, eta, self.alpha);
+            if pdf <= 0.0 {
+                return None;
+            }
+            return Some(ScatterRecord::new(
+                frame.to_world(wi),
+                Color::from(transmittance * value),
+                pdf,
+            ));
+        }
+
+        let ri = 1.0 / eta;
 
         let unit_direction = ray.direction.normalize();
         let cos_theta = (-unit_direction).dot(hit.normal).min(1.0);
@@ -38,8 +173,50 @@ impl Material for Dielectric {
             unit_direction.refract(hit.normal, ri)
         };
 
-        let scattered = Ray::new(hit.point, direction);
+        Some(ScatterRecord::specular(
+            direction,
+            Color::from(transmittance),
+        ))
+    }
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        if !self.needs_wavelength(ray) {
+            return self.scatter_at(ray, hit, ray.wavelength);
+        }
+
+        // Pick the path's wavelength here and weight it by its color. The sample can't be
+        // matched by light sampling, which never sees the wavelength, so it counts as specular.
+        let wavelength = sample_wavelength();
+        let record = self.scatter_at(ray, hit, Some(wavelength))?;
+        let weight = record.weight(hit) * wavelength_response(wavelength);
+        Some(
+            ScatterRecord::specular(record.direction, Color::from(weight))
+                .with_wavelength(wavelength),
+        )
+    }
+
+    fn eval(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> Color<Linear> {
+        if self.is_smooth() || self.needs_wavelength(ray) {
+            return Color::from(Vec3::ZERO);
+        }
+
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        let (value, _) = ggx::dielectric(wo, wi, self.eta(hit, ray.wavelength), self.alpha);
+        Color::from(self.transmittance(ray, hit) * value)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> f32 {
+        if self.is_smooth() || self.needs_wavelength(ray) {
+            return 0.0;
+        }
 
-        Some((scattered, Color::from(Vec3::ONE)))
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        ggx::dielectric(wo, wi, self.eta(hit, ray.wavelength), self.alpha).1
     }
 }
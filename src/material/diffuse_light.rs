@@ -0,0 +1,28 @@
+use crate::{
+    color::{Color, Linear},
+    hittable::HitRecord,
+    ray::Ray,
+};
+
+use super::{Material, ScatterRecord};
+
+#[derive(Clone, Debug)]
+pub struct DiffuseLight {
+    emit: Color<Linear>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color<Linear>) -> Self {
+        Self { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: &Ray, _hit: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, _ray: &Ray, _hit: &HitRecord) -> Color<Linear> {
+        self.emit
+    }
+}
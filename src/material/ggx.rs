@@ -0,0 +1,177 @@
+// GGX (Trowbridge-Reitz) microfacet distribution with height-correlated Smith masking.
+// Directions are in the local shading frame with the macro normal along +Z.
+
+use std::f32::consts::{PI, TAU};
+
+use crate::vec::Vec3;
+
+// Below this alpha the lobe is treated as a perfect mirror or refraction.
+pub(crate) const SPECULAR_ALPHA: f32 = 1e-4;
+
+// Squares perceptual roughness into alpha the way most authoring tools do.
+pub(crate) fn alpha_from_roughness(roughness: f32) -> f32 {
+    let roughness = roughness.clamp(0.0, 1.0);
+    roughness * roughness
+}
+
+// Microfacet normal density, normalized so that its projection onto +Z integrates to 1.
+pub(crate) fn d(h: Vec3, alpha: f32) -> f32 {
+    if h.z <= 0.0 {
+        return 0.0;
+    }
+    let alpha2 = alpha * alpha;
+    let denom = h.z * h.z * (alpha2 - 1.0) + 1.0;
+    alpha2 / (PI * denom * denom)
+}
+
+fn lambda(w: Vec3, alpha: f32) -> f32 {
+    let cos2 = w.z * w.z;
+    if cos2 <= 0.0 {
+        return f32::INFINITY;
+    }
+    let tan2 = (1.0 - cos2).max(0.0) / cos2;
+    0.5 * ((1.0 + alpha * alpha * tan2).sqrt() - 1.0)
+}
+
+pub(crate) fn g1(w: Vec3, alpha: f32) -> f32 {
+    1.0 / (1.0 + lambda(w, alpha))
+}
+
+pub(crate) fn g(wo: Vec3, wi: Vec3, alpha: f32) -> f32 {
+    1.0 / (1.0 + lambda(wo, alpha) + lambda(wi, alpha))
+}
+
+// Samples a microfacet normal from the normals visible from `wo` (Heitz 2018).
+pub(crate) fn sample_visible_normal(wo: Vec3, alpha: f32, u1: f32, u2: f32) -> Vec3 {
+    let vh = Vec3::new(alpha * wo.x, alpha * wo.y, wo.z).normalize();
+
+    let length_squared = vh.x * vh.x + vh.y * vh.y;
+    let t1 = if length_squared > 0.0 {
+        Vec3::new(-vh.y, vh.x, 0.0) / length_squared.sqrt()
+    } else {
+        Vec3::X
+    };
+    let t2 = vh.cross(t1);
+
+    let r = u1.sqrt();
+    let phi = TAU * u2;
+    let p1 = r * phi.cos();
+    let s = 0.5 * (1.0 + vh.z);
+    let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * r * phi.sin();
+
+    let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+    Vec3::new(alpha * nh.x, alpha * nh.y, nh.z.max(1e-6)).normalize()
+}
+
+// Density of `sample_visible_normal` returning `h`, per unit solid angle of `h`.
+pub(crate) fn visible_normal_pdf(wo: Vec3, h: Vec3, alpha: f32) -> f32 {
+    if wo.z <= 0.0 {
+        return 0.0;
+    }
+    g1(wo, alpha) * wo.dot(h).max(0.0) * d(h, alpha) / wo.z
+}
+
+pub(crate) fn reflect(wo: Vec3, h: Vec3) -> Vec3 {
+    2.0 * wo.dot(h) * h - wo
+}
+
+pub(crate) fn fresnel_schlick(f0: Vec3, cos_theta: f32) -> Vec3 {
+    f0 + (Vec3::ONE - f0) * (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+// Unpolarized reflectance of a conductor with complex index of refraction `eta + i k`.
+pub(crate) fn fresnel_conductor(cos_theta: f32, eta: Vec3, k: Vec3) -> Vec3 {
+    let cos2 = cos_theta.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+    let (eta2, k2) = (eta * eta, k * k);
+
+    let t0 = eta2 - k2 - Vec3::splat(sin2);
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).max(Vec3::ZERO).powf(0.5);
+    let t1 = a2_plus_b2 + Vec3::splat(cos2);
+    let a = (0.5 * (a2_plus_b2 + t0)).max(Vec3::ZERO).powf(0.5);
+    let t2 = 2.0 * cos_theta.clamp(0.0, 1.0) * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + Vec3::splat(sin2 * sin2);
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+// Reflectance of a dielectric interface for light arriving at `cos_theta` from the side whose
+// relative index of refraction (far side over near side) is `eta`.
+pub(crate) fn fresnel_dielectric(cos_theta: f32, eta: f32) -> f32 {
+    let (cos_i, eta) = if cos_theta < 0.0 {
+        (-cos_theta, 1.0 / eta)
+    } else {
+        (cos_theta, eta)
+    };
+
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+
+    let r_parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (r_parallel * r_parallel + r_perpendicular * r_perpendicular)
+}
+
+// Refracts `wo` through a microfacet with normal `h`, or None on total internal reflection.
+pub(crate) fn refract(wo: Vec3, h: Vec3, eta: f32) -> Option<Vec3> {
+    let cos_i = wo.dot(h);
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 {
+        return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-wo / eta + (cos_i / eta - cos_t) * h)
+}
+
+// Rough dielectric BSDF (Walter et al. 2007) and the density of `sample_dielectric`, for `wo`
+// above the surface and relative index of refraction `eta` across it.
+pub(crate) fn dielectric(wo: Vec3, wi: Vec3, eta: f32, alpha: f32) -> (f32, f32) {
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let reflect = wi.z > 0.0;
+    let etap = if reflect { 1.0 } else { eta };
+    let Some(h) = (wi * etap + wo).try_normalize() else {
+        return (0.0, 0.0);
+    };
+    let h = if h.z < 0.0 { -h } else { h };
+
+    // Microfacets facing away from either direction can't connect them.
+    if h.dot(wi) * wi.z < 0.0 || h.dot(wo) < 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let fresnel = fresnel_dielectric(wo.dot(h), eta);
+    let visible_pdf = visible_normal_pdf(wo, h, alpha);
+    let dg = d(h, alpha) * g(wo, wi, alpha);
+
+    if reflect {
+        let value = dg * fresnel / (4.0 * wo.z * wi.z);
+        let pdf = visible_pdf / (4.0 * wo.dot(h)) * fresnel;
+        (value, pdf)
+    } else {
+        let denom = (wi.dot(h) + wo.dot(h) / etap).powi(2);
+        let value = dg * (1.0 - fresnel) * (wi.dot(h) * wo.dot(h) / (denom * wi.z * wo.z)).abs();
+        let pdf = visible_pdf * wi.dot(h).abs() / denom * (1.0 - fresnel);
+        (value, pdf)
+    }
+}
+
+// Picks reflection or refraction through a visible microfacet in proportion to its Fresnel
+// reflectance; `u` holds three uniform samples.
+pub(crate) fn sample_dielectric(wo: Vec3, eta: f32, alpha: f32, u: [f32; 3]) -> Option<Vec3> {
+    let h = sample_visible_normal(wo, alpha, u[0], u[1]);
+    if u[2] < fresnel_dielectric(wo.dot(h), eta) {
+        Some(reflect(wo, h)).filter(|wi| wi.z > 0.0)
+    } else {
+        refract(wo, h, eta).filter(|wi| wi.z < 0.0)
+    }
+}
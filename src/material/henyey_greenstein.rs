@@ -0,0 +1,76 @@
+use std::{
+    f32::consts::{PI, TAU},
+    sync::Arc,
+};
+
+use crate::{
+    hittable::HitRecord,
+    ray::Ray,
+    texture::{solid::SolidColor, Texture},
+    utils::Random,
+    vec::{Onb, Vec3},
+};
+
+use super::{Color, Linear, Material, ScatterRecord};
+
+// Phase function of a medium that favors scattering forward (g > 0), such as haze and clouds,
+// or backward (g < 0). The asymmetry g is the mean cosine of the scattering angle.
+#[derive(Clone, Debug)]
+pub struct HenyeyGreenstein {
+    albedo: Arc<dyn Texture>,
+    g: f32,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Color<Linear>, g: f32) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), g)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, g: f32) -> Self {
+        Self {
+            albedo,
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+
+    // Density over the sphere of scattering by an angle with cosine `cos_theta` from the
+    // direction of travel.
+    fn phase(&self, cos_theta: f32) -> f32 {
+        let g = self.g;
+        let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let g = self.g;
+        let u = f32::random();
+        let cos_theta = if g.abs() < 1e-3 {
+            1.0 - 2.0 * u
+        } else {
+            let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * u);
+            ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = TAU * f32::random();
+
+        let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        let direction = Onb::new(ray.direction).to_world(local);
+        Some(ScatterRecord::new(
+            direction,
+            self.eval(ray, hit, direction),
+            self.phase(cos_theta),
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> Color<Linear> {
+        let albedo = self.albedo.value(hit.uv, hit.point).v;
+        Color::from(albedo * self.pdf(ray, hit, direction))
+    }
+
+    fn pdf(&self, ray: &Ray, _hit: &HitRecord, direction: Vec3) -> f32 {
+        let cos_theta = ray.direction.normalize().dot(direction.normalize());
+        self.phase(cos_theta)
+    }
+}
@@ -0,0 +1,46 @@
+use std::{f32::consts::PI, sync::Arc};
+
+use crate::{
+    hittable::HitRecord,
+    ray::Ray,
+    sampling::uniform_sphere_pdf,
+    texture::{solid::SolidColor, Texture},
+    vec::{Vec3, VecExt},
+};
+
+use super::{Color, Linear, Material, ScatterRecord};
+
+// Phase function of a medium that scatters equally in every direction.
+#[derive(Clone, Debug)]
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color<Linear>) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let direction = Vec3::random_unit();
+        Some(ScatterRecord::new(
+            direction,
+            self.eval(ray, hit, direction),
+            uniform_sphere_pdf(),
+        ))
+    }
+
+    fn eval(&self, _ray: &Ray, hit: &HitRecord, _direction: Vec3) -> Color<Linear> {
+        Color::from(self.albedo.value(hit.uv, hit.point).v / (4.0 * PI))
+    }
+
+    fn pdf(&self, _ray: &Ray, _hit: &HitRecord, _direction: Vec3) -> f32 {
+        uniform_sphere_pdf()
+    }
+}
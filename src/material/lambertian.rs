@@ -1,32 +1,49 @@
+use std::{f32::consts::FRAC_1_PI, sync::Arc};
+
 use crate::{
     hittable::HitRecord,
     ray::Ray,
-    vec::{Vec3, VecExt},
+    sampling::cosine_hemisphere_pdf,
+    texture::{solid::SolidColor, Texture},
+    vec::{Onb, Vec3, VecExt},
 };
 
-use super::{Color, Linear, Material};
+use super::{Color, Linear, Material, ScatterRecord};
 
 #[derive(Clone, Debug)]
 pub struct Lambertian {
-    albedo: Color<Linear>,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color<Linear>) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
         Self { albedo }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color<Linear>)> {
-        let mut scatter_direction = hit.normal.clone() + Vec3::random_unit();
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let direction = Onb::new(hit.normal).to_world(Vec3::random_cosine_direction());
+        Some(ScatterRecord::new(
+            direction,
+            self.eval(ray, hit, direction),
+            self.pdf(ray, hit, direction),
+        ))
+    }
 
-        if scatter_direction.near_zero() {
-            scatter_direction = hit.normal.clone();
+    fn eval(&self, _ray: &Ray, hit: &HitRecord, direction: Vec3) -> Color<Linear> {
+        if hit.normal.dot(direction) > 0.0 {
+            Color::from(self.albedo.value(hit.uv, hit.point).v * FRAC_1_PI)
+        } else {
+            Color::from(Vec3::ZERO)
         }
+    }
 
-        let scattered = Ray::new(hit.point.clone(), scatter_direction);
-        let attenuation = self.albedo.clone();
-        Some((scattered, attenuation))
+    fn pdf(&self, _ray: &Ray, hit: &HitRecord, direction: Vec3) -> f32 {
+        cosine_hemisphere_pdf(hit.normal.dot(direction.normalize()))
     }
 }
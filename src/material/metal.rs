@@ -1,33 +1,44 @@
+use std::sync::Arc;
+
 use crate::{
     color::{Color, Linear},
     hittable::HitRecord,
     ray::Ray,
+    texture::{solid::SolidColor, Texture},
     vec::{Vec3, VecExt},
 };
 
-use super::Material;
+use super::{Material, ScatterRecord};
 
 #[derive(Clone, Debug)]
 pub struct Metal {
-    albedo: Color<Linear>,
-    fuzz: f32,
+    albedo: Arc<dyn Texture>,
+    fuzz: Arc<dyn Texture>,
 }
 
 impl Metal {
     pub fn new(albedo: Color<Linear>, fuzz: f32) -> Self {
-        let fuzz = if fuzz < 1.0 { fuzz } else { 1.0 };
+        Self::from_texture(
+            Arc::new(SolidColor::new(albedo)),
+            Arc::new(SolidColor::gray(fuzz)),
+        )
+    }
+
+    // `fuzz` is read as a scalar texture.
+    pub fn from_texture(albedo: Arc<dyn Texture>, fuzz: Arc<dyn Texture>) -> Self {
         Self { albedo, fuzz }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<(Ray, Color<Linear>)> {
+    // The fuzzed lobe has no closed-form density, so it is treated as specular.
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let fuzz = self.fuzz.scalar(hit.uv, hit.point).min(1.0);
         let reflected =
-            ray.direction().reflect(hit.normal).normalize() + Vec3::random_unit() * self.fuzz;
-        let scattered = Ray::new(hit.point, reflected);
-        let attenuation = self.albedo;
-        if scattered.direction().dot(hit.normal) > 0.0 {
-            Some((scattered, attenuation))
+            ray.direction().reflect(hit.normal).normalize() + Vec3::random_unit() * fuzz;
+        if reflected.dot(hit.normal) > 0.0 {
+            let albedo = self.albedo.value(hit.uv, hit.point);
+            Some(ScatterRecord::specular(reflected, albedo))
         } else {
             None
         }
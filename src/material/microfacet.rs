@@ -0,0 +1,126 @@
+use crate::{
+    color::{Color, Linear},
+    hittable::HitRecord,
+    ray::Ray,
+    utils::Random,
+    vec::{Onb, Vec3},
+};
+
+use super::{ggx, Material, ScatterRecord};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Fresnel {
+    // Reflectance at normal incidence, with Schlick's approximation elsewhere.
+    Schlick(Color<Linear>),
+    // Complex index of refraction `eta + i k` per channel, e.g. gold is
+    // eta (0.143, 0.374, 1.442), k (3.983, 2.385, 1.603).
+    Conductor {
+        eta: Color<Linear>,
+        k: Color<Linear>,
+    },
+}
+
+impl Fresnel {
+    fn reflectance(&self, cos_theta: f32) -> Vec3 {
+        match self {
+            Fresnel::Schlick(f0) => ggx::fresnel_schlick(f0.v, cos_theta),
+            Fresnel::Conductor { eta, k } => ggx::fresnel_conductor(cos_theta, eta.v, k.v),
+        }
+    }
+}
+
+// Rough metal using the GGX distribution. `roughness` is perceptual roughness in [0, 1];
+// 0 is a perfect mirror.
+#[derive(Clone, Debug)]
+pub struct Microfacet {
+    fresnel: Fresnel,
+    alpha: f32,
+}
+
+impl Microfacet {
+    pub fn new(reflectance: Color<Linear>, roughness: f32) -> Self {
+        Self::with_fresnel(Fresnel::Schlick(reflectance), roughness)
+    }
+
+    pub fn conductor(eta: Color<Linear>, k: Color<Linear>, roughness: f32) -> Self {
+        Self::with_fresnel(Fresnel::Conductor { eta, k }, roughness)
+    }
+
+    pub fn with_fresnel(fresnel: Fresnel, roughness: f32) -> Self {
+        Self {
+            fresnel,
+            alpha: ggx::alpha_from_roughness(roughness),
+        }
+    }
+
+    fn is_specular(&self) -> bool {
+        self.alpha < ggx::SPECULAR_ALPHA
+    }
+}
+
+impl Material for Microfacet {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        if self.is_specular() {
+            let wi = Vec3::new(-wo.x, -wo.y, wo.z);
+            let weight = self.fresnel.reflectance(wo.z);
+            return Some(ScatterRecord::specular(
+                frame.to_world(wi),
+                Color::from(weight),
+            ));
+        }
+
+        let h = ggx::sample_visible_normal(wo, self.alpha, f32::random(), f32::random());
+        let wi = ggx::reflect(wo, h);
+        if wi.z <= 0.0 {
+            return None;
+        }
+
+        let direction = frame.to_world(wi);
+        Some(ScatterRecord::new(
+            direction,
+            self.eval(ray, hit, direction),
+            self.pdf(ray, hit, direction),
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> Color<Linear> {
+        if self.is_specular() {
+            return Color::from(Vec3::ZERO);
+        }
+
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Color::from(Vec3::ZERO);
+        }
+
+        let h = (wo + wi).normalize();
+        let f = self.fresnel.reflectance(wo.dot(h))
+            * (ggx::d(h, self.alpha) * ggx::g(wo, wi, self.alpha) / (4.0 * wo.z * wi.z));
+        Color::from(f)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> f32 {
+        if self.is_specular() {
+            return 0.0;
+        }
+
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return 0.0;
+        }
+
+        // Change of variables from the half vector to the reflected direction.
+        let h = (wo + wi).normalize();
+        ggx::visible_normal_pdf(wo, h, self.alpha) / (4.0 * wo.dot(h))
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::{
+    color::{Color, Linear},
+    hittable::HitRecord,
+    ray::Ray,
+    texture::Texture,
+    vec::{Vec2, Vec3},
+};
+
+use super::{Material, ScatterRecord};
+
+// UV step for the finite differences of bump maps.
+const BUMP_DELTA: f32 = 5e-4;
+
+#[derive(Clone, Debug)]
+pub enum NormalMapping {
+    // Tangent-space normals stored as `0.5 * (n + 1)`, with +Z pointing away from the surface.
+    // The texture must hold raw data rather than sRGB colors.
+    Normal(Arc<dyn Texture>),
+    // Height field whose values are scaled into world units.
+    Bump {
+        height: Arc<dyn Texture>,
+        scale: f32,
+    },
+}
+
+// Wraps a material to tilt its shading normal with a normal or bump map.
+#[derive(Clone, Debug)]
+pub struct NormalMapped {
+    material: Arc<dyn Material>,
+    mapping: NormalMapping,
+}
+
+impl NormalMapped {
+    pub fn new(material: Arc<dyn Material>, mapping: NormalMapping) -> Self {
+        Self { material, mapping }
+    }
+
+    pub fn normal_map(material: Arc<dyn Material>, texture: Arc<dyn Texture>) -> Self {
+        Self::new(material, NormalMapping::Normal(texture))
+    }
+
+    pub fn bump_map(material: Arc<dyn Material>, height: Arc<dyn Texture>, scale: f32) -> Self {
+        Self::new(material, NormalMapping::Bump { height, scale })
+    }
+}
+
+impl Material for NormalMapped {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        self.material.scatter(ray, hit)
+    }
+
+    fn eval(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> Color<Linear> {
+        self.material.eval(ray, hit, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> f32 {
+        self.material.pdf(ray, hit, direction)
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Color<Linear> {
+        self.material.emitted(ray, hit)
+    }
+
+    fn shading_normal(&self, hit: &HitRecord) -> Option<Vec3> {
+        let normal = self.material.shading_normal(hit).unwrap_or(hit.normal);
+
+        match &self.mapping {
+            NormalMapping::Normal(texture) => {
+                let tangent = (hit.dpdu - normal * normal.dot(hit.dpdu)).try_normalize()?;
+                let mut bitangent = normal.cross(tangent);
+                if bitangent.dot(hit.dpdv) < 0.0 {
+                    bitangent = -bitangent;
+                }
+
+                let local = 2.0 * texture.value(hit.uv, hit.point).v - Vec3::ONE;
+                Some(local.x * tangent + local.y * bitangent + local.z * normal)
+            }
+            NormalMapping::Bump { height, scale } => {
+                let (du, dv) = (BUMP_DELTA, BUMP_DELTA);
+                let displacement = |uv_offset, point_offset| {
+                    let uv = hit.uv + uv_offset;
+                    scale * height.scalar(uv, hit.point + point_offset)
+                };
+
+                let base = displacement(Vec2::ZERO, Vec3::ZERO);
+                let along_u = displacement(Vec2::new(du, 0.0), du * hit.dpdu);
+                let along_v = displacement(Vec2::new(0.0, dv), dv * hit.dpdv);
+
+                let dpdu = hit.dpdu + (along_u - base) / du * normal;
+                let dpdv = hit.dpdv + (along_v - base) / dv * normal;
+                let bumped = dpdu.cross(dpdv);
+
+                // Keep the orientation of the unperturbed normal.
+                Some(if bumped.dot(normal) < 0.0 {
+                    -bumped
+                } else {
+                    bumped
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::f32::consts::FRAC_1_PI;
+
+use crate::{
+    color::{Color, Linear},
+    hittable::HitRecord,
+    ray::Ray,
+    sampling::cosine_hemisphere_pdf,
+    utils::Random,
+    vec::{Onb, Vec3, VecExt},
+};
+
+use super::{ggx, Material, ScatterRecord};
+
+// Lobes are clamped to this alpha so every one of them can be evaluated and light sampled.
+const MIN_ALPHA: f32 = 1e-3;
+
+// Disney-style layered material: a diffuse base with sheen, a GGX specular layer that turns
+// into a conductor as `metallic` goes to 1, rough glass for `transmission` and a clearcoat.
+// Parameters other than the colors and `ior` are in [0, 1].
+#[derive(Clone, Debug)]
+pub struct Principled {
+    base_color: Color<Linear>,
+    metallic: f32,
+    roughness: f32,
+    specular: f32,
+    clearcoat: f32,
+    clearcoat_roughness: f32,
+    sheen: f32,
+    transmission: f32,
+    ior: f32,
+}
+
+impl Principled {
+    pub fn new(base_color: Color<Linear>) -> Self {
+        Self {
+            base_color,
+            metallic: 0.0,
+            roughness: 0.5,
+            specular: 0.5,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.03,
+            sheen: 0.0,
+            transmission: 0.0,
+            ior: 1.5,
+        }
+    }
+
+    pub fn with_metallic(self, metallic: f32) -> Self {
+        Self {
+            metallic: metallic.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_roughness(self, roughness: f32) -> Self {
+        Self {
+            roughness: roughness.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    // Dielectric reflectance at normal incidence, scaled so the default 0.5 gives 4%.
+    pub fn with_specular(self, specular: f32) -> Self {
+        Self {
+            specular: specular.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_clearcoat(self, clearcoat: f32, roughness: f32) -> Self {
+        Self {
+            clearcoat: clearcoat.clamp(0.0, 1.0),
+            clearcoat_roughness: roughness.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_sheen(self, sheen: f32) -> Self {
+        Self {
+            sheen: sheen.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_transmission(self, transmission: f32) -> Self {
+        Self {
+            transmission: transmission.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_ior(self, ior: f32) -> Self {
+        Self { ior, ..self }
+    }
+
+    fn alpha(&self) -> f32 {
+        ggx::alpha_from_roughness(self.roughness).max(MIN_ALPHA)
+    }
+
+    fn clearcoat_alpha(&self) -> f32 {
+        ggx::alpha_from_roughness(self.clearcoat_roughness).max(MIN_ALPHA)
+    }
+
+    // Weights of the diffuse, specular, glass and clearcoat lobes.
+    fn weights(&self) -> [f32; 4] {
+        let dielectric = 1.0 - self.metallic;
+        [
+            dielectric * (1.0 - self.transmission),
+            1.0 - dielectric * self.transmission,
+            dielectric * self.transmission,
+            self.clearcoat,
+        ]
+    }
+
+    // Probabilities of sampling each lobe, roughly following how much light it reflects.
+    fn probabilities(&self) -> [f32; 4] {
+        let [diffuse, specular, glass, clearcoat] = self.weights();
+        let base = luminance(self.base_color.v).max(0.05);
+        let f0 = luminance(self.f0()).max(0.05);
+        let lobes = [diffuse * base, specular * f0, glass, 0.25 * clearcoat];
+
+        let total: f32 = lobes.iter().sum();
+        if total > 0.0 {
+            lobes.map(|p| p / total)
+        } else {
+            [1.0, 0.0, 0.0, 0.0]
+        }
+    }
+
+    fn f0(&self) -> Vec3 {
+        Vec3::splat(0.08 * self.specular).lerp(self.base_color.v, self.metallic)
+    }
+
+    // Relative index of refraction across the surface for a ray arriving on its `hit` side.
+    fn eta(&self, hit: &HitRecord) -> f32 {
+        if hit.front_face {
+            self.ior
+        } else {
+            1.0 / self.ior
+        }
+    }
+
+    fn eval_local(&self, wo: Vec3, wi: Vec3, eta: f32) -> Vec3 {
+        if wo.z <= 0.0 {
+            return Vec3::ZERO;
+        }
+        let [diffuse, specular, glass, clearcoat] = self.weights();
+        let mut f = Vec3::ZERO;
+
+        if wi.z > 0.0 {
+            let h = (wo + wi).normalize();
+            let cos_d = wi.dot(h);
+
+            if diffuse > 0.0 {
+                // Burley's retro-reflective diffuse plus a grazing sheen.
+                let fd90 = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+                let fresnel_in = 1.0 + (fd90 - 1.0) * (1.0 - wi.z).powi(5);
+                let fresnel_out = 1.0 + (fd90 - 1.0) * (1.0 - wo.z).powi(5);
+                let base = self.base_color.v * (FRAC_1_PI * fresnel_in * fresnel_out);
+                let sheen = Vec3::splat(self.sheen * (1.0 - cos_d).powi(5));
+                f += diffuse * (base + sheen);
+            }
+
+            if specular > 0.0 {
+                let alpha = self.alpha();
+                let dg = ggx::d(h, alpha) * ggx::g(wo, wi, alpha);
+                f += specular * ggx::fresnel_schlick(self.f0(), wo.dot(h)) * dg
+                    / (4.0 * wo.z * wi.z);
+            }
+
+            if clearcoat > 0.0 {
+                let alpha = self.clearcoat_alpha();
+                let dg = ggx::d(h, alpha) * ggx::g(wo, wi, alpha);
+                f += clearcoat * ggx::fresnel_schlick(Vec3::splat(0.04), wo.dot(h)) * dg
+                    / (4.0 * wo.z * wi.z);
+            }
+        }
+
+        if glass > 0.0 {
+            let (value, _) = ggx::dielectric(wo, wi, eta, self.alpha());
+            // Only light passing through the surface picks up the base color. Each crossing
+            // applies its square root, so going in and out of a closed object tints it once.
+            let tint = if wi.z < 0.0 {
+                self.base_color.v.powf(0.5)
+            } else {
+                Vec3::ONE
+            };
+            f += glass * value * tint;
+        }
+
+        f
+    }
+
+    fn pdf_local(&self, wo: Vec3, wi: Vec3, eta: f32) -> f32 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+        let [diffuse, specular, glass, clearcoat] = self.probabilities();
+        let mut pdf = 0.0;
+
+        if wi.z > 0.0 {
+            let h = (wo + wi).normalize();
+            pdf += diffuse * cosine_hemisphere_pdf(wi.z);
+            pdf += specular * ggx::visible_normal_pdf(wo, h, self.alpha()) / (4.0 * wo.dot(h));
+            pdf += clearcoat * ggx::visible_normal_pdf(wo, h, self.clearcoat_alpha())
+                / (4.0 * wo.dot(h));
+        }
+
+        if glass > 0.0 {
+            pdf += glass * ggx::dielectric(wo, wi, eta, self.alpha()).1;
+        }
+
+        pdf
+    }
+
+    fn sample_local(&self, wo: Vec3, eta: f32) -> Option<Vec3> {
+        let [diffuse, specular, glass, _] = self.probabilities();
+        let (lobe, u1, u2) = (f32::random(), f32::random(), f32::random());
+
+        let alpha = if lobe < diffuse {
+            return Some(Vec3::random_cosine_direction());
+        } else if lobe < diffuse + specular {
+            self.alpha()
+        } else if lobe < diffuse + specular + glass {
+            return ggx::sample_dielectric(wo, eta, self.alpha(), [u1, u2, f32::random()]);
+        } else {
+            self.clearcoat_alpha()
+        };
+
+        let h = ggx::sample_visible_normal(wo, alpha, u1, u2);
+        Some(ggx::reflect(wo, h)).filter(|wi| wi.z > 0.0)
+    }
+}
+
+fn luminance(color: Vec3) -> f32 {
+    color.dot(Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let eta = self.eta(hit);
+        let wi = self.sample_local(wo, eta)?;
+        let pdf = self.pdf_local(wo, wi, eta);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord::new(
+            frame.to_world(wi),
+            Color::from(self.eval_local(wo, wi, eta)),
+            pdf,
+        ))
+    }
+
+    fn eval(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> Color<Linear> {
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        Color::from(self.eval_local(wo, wi, self.eta(hit)))
+    }
+
+    fn pdf(&self, ray: &Ray, hit: &HitRecord, direction: Vec3) -> f32 {
+        let frame = Onb::new(hit.normal);
+        let wo = frame.to_local(-ray.direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        self.pdf_local(wo, wi, self.eta(hit))
+    }
+}
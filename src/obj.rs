@@ -0,0 +1,562 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::{FromStr, SplitWhitespace},
+    sync::Arc,
+};
+
+use image::ImageError;
+
+use crate::{
+    color::{Color, Linear},
+    hittable::mesh::{MeshBuffers, TriangleMesh},
+    material::{
+        dielectric::Dielectric, lambertian::Lambertian, microfacet::Microfacet,
+        normal_map::NormalMapped, principled::Principled, Material,
+    },
+    point::Point3,
+    texture::{image::ImageTexture, Texture},
+    vec::{Vec2, Vec3},
+};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    Image {
+        path: PathBuf,
+        source: ImageError,
+    },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            ObjError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ObjError::Image { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io { source, .. } => Some(source),
+            ObjError::Parse { .. } => None,
+            ObjError::Image { source, .. } => Some(source),
+        }
+    }
+}
+
+pub type ObjResult<T> = Result<T, ObjError>;
+
+pub fn load(path: impl AsRef<Path>) -> ObjResult<Vec<TriangleMesh>> {
+    load_meshes(path.as_ref(), None)
+}
+
+// Ignores the referenced material libraries and uses `material` for every mesh.
+pub fn load_with_material(
+    path: impl AsRef<Path>,
+    material: Arc<dyn Material>,
+) -> ObjResult<Vec<TriangleMesh>> {
+    load_meshes(path.as_ref(), Some(material))
+}
+
+fn load_meshes(
+    path: &Path,
+    material_override: Option<Arc<dyn Material>>,
+) -> ObjResult<Vec<TriangleMesh>> {
+    let source = read(path)?;
+    let mut parser = ObjParser::new(path, material_override);
+
+    for (index, line) in source.lines().enumerate() {
+        parser.line(index + 1, line)?;
+    }
+
+    Ok(parser.finish())
+}
+
+fn read(path: &Path) -> ObjResult<String> {
+    fs::read_to_string(path).map_err(|source| ObjError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parse_error(path: &Path, line: usize, message: String) -> ObjError {
+    ObjError::Parse {
+        path: path.to_path_buf(),
+        line,
+        message,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct VertexKey {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+#[derive(Default)]
+struct MeshBuilder {
+    vertices: HashMap<VertexKey, u32>,
+    keys: Vec<VertexKey>,
+    indices: Vec<[u32; 3]>,
+}
+
+impl MeshBuilder {
+    fn vertex(&mut self, key: VertexKey) -> u32 {
+        *self.vertices.entry(key).or_insert_with(|| {
+            self.keys.push(key);
+            (self.keys.len() - 1) as u32
+        })
+    }
+
+    fn build(self, positions: &[Point3], uvs: &[Vec2], normals: &[Vec3]) -> MeshBuffers {
+        let has_uvs = self.keys.iter().all(|key| key.uv.is_some());
+        let has_normals = self.keys.iter().all(|key| key.normal.is_some());
+
+        MeshBuffers {
+            positions: self
+                .keys
+                .iter()
+                .map(|key| positions[key.position])
+                .collect(),
+            normals: if has_normals {
+                self.keys
+                    .iter()
+                    .filter_map(|key| key.normal.map(|i| normals[i]))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+            uvs: if has_uvs {
+                self.keys
+                    .iter()
+                    .filter_map(|key| key.uv.map(|i| uvs[i]))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+            indices: self.indices,
+        }
+    }
+}
+
+struct ObjParser<'a> {
+    path: &'a Path,
+    positions: Vec<Point3>,
+    uvs: Vec<Vec2>,
+    normals: Vec<Vec3>,
+    materials: HashMap<String, Arc<dyn Material>>,
+    material_override: Option<Arc<dyn Material>>,
+    current_material: Option<String>,
+    meshes: Vec<(Option<String>, MeshBuilder)>,
+}
+
+impl<'a> ObjParser<'a> {
+    fn new(path: &'a Path, material_override: Option<Arc<dyn Material>>) -> ObjParser<'a> {
+        ObjParser {
+            path,
+            material_override,
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            materials: HashMap::new(),
+            current_material: None,
+            meshes: Vec::new(),
+        }
+    }
+
+    fn line(&mut self, number: usize, line: &str) -> ObjResult<()> {
+        let line = strip_comment(line);
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            return Ok(());
+        };
+        let error = |message: String| parse_error(self.path, number, message);
+
+        if keyword == "mtllib" {
+            if self.material_override.is_some() {
+                return Ok(());
+            }
+            let directory = self.path.parent().unwrap_or(Path::new(""));
+            let library = directory.join(rest(line, keyword).map_err(error)?);
+            self.materials.extend(load_mtl(library)?);
+            return Ok(());
+        }
+
+        self.statement(keyword, tokens, line).map_err(error)
+    }
+
+    fn statement(
+        &mut self,
+        keyword: &str,
+        mut tokens: SplitWhitespace,
+        line: &str,
+    ) -> Result<(), String> {
+        match keyword {
+            "v" => self
+                .positions
+                .push(Point3::from(parse_floats::<3>(&mut tokens)?)),
+            "vt" => {
+                let u = parse_float(tokens.next())?;
+                let v = tokens.next().map_or(Ok(0.0), |t| parse_float(Some(t)))?;
+                self.uvs.push(Vec2::new(u, v));
+            }
+            "vn" => self
+                .normals
+                .push(Vec3::from(parse_floats::<3>(&mut tokens)?)),
+            "f" => self.face(tokens)?,
+            "usemtl" => self.current_material = Some(rest(line, keyword)?.to_string()),
+            // Groups, smoothing groups, curves and the like carry nothing we can render.
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn face(&mut self, tokens: SplitWhitespace) -> Result<(), String> {
+        let keys = tokens
+            .map(|token| self.vertex_key(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.len() < 3 {
+            return Err("face needs at least three vertices".to_string());
+        }
+
+        let mesh = match self
+            .meshes
+            .iter()
+            .position(|(material, _)| *material == self.current_material)
+        {
+            Some(i) => &mut self.meshes[i].1,
+            None => {
+                self.meshes
+                    .push((self.current_material.clone(), MeshBuilder::default()));
+                &mut self.meshes.last_mut().expect("mesh was just pushed").1
+            }
+        };
+
+        let indices: Vec<u32> = keys.into_iter().map(|key| mesh.vertex(key)).collect();
+        for i in 1..indices.len() - 1 {
+            mesh.indices.push([indices[0], indices[i], indices[i + 1]]);
+        }
+
+        Ok(())
+    }
+
+    fn vertex_key(&self, token: &str) -> Result<VertexKey, String> {
+        let mut parts = token.split('/');
+        let position = resolve_index(parts.next(), self.positions.len())?
+            .ok_or_else(|| format!("face vertex `{}` has no position", token))?;
+        let uv = resolve_index(parts.next(), self.uvs.len())?;
+        let normal = resolve_index(parts.next(), self.normals.len())?;
+
+        Ok(VertexKey {
+            position,
+            uv,
+            normal,
+        })
+    }
+
+    fn finish(self) -> Vec<TriangleMesh> {
+        let default_material: Arc<dyn Material> =
+            Arc::new(Lambertian::new(Color::new(0.8, 0.8, 0.8)));
+
+        self.meshes
+            .into_iter()
+            .map(|(name, builder)| {
+                let material = self
+                    .material_override
+                    .clone()
+                    .or_else(|| name.and_then(|name| self.materials.get(&name).cloned()))
+                    .unwrap_or_else(|| default_material.clone());
+                let buffers = builder.build(&self.positions, &self.uvs, &self.normals);
+                TriangleMesh::new(buffers, material)
+            })
+            .collect()
+    }
+}
+
+pub fn load_mtl(path: impl AsRef<Path>) -> ObjResult<HashMap<String, Arc<dyn Material>>> {
+    let path = path.as_ref();
+    let source = read(path)?;
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlParameters)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line = strip_comment(line);
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let error = |message: String| parse_error(path, index + 1, message);
+
+        if keyword == "newmtl" {
+            if let Some((name, parameters)) = current.take() {
+                materials.insert(name, parameters.to_material(directory)?);
+            }
+            let name = rest(line, keyword).map_err(error)?;
+            current = Some((name.to_string(), MtlParameters::default()));
+            continue;
+        }
+
+        let Some((_, parameters)) = current.as_mut() else {
+            return Err(error(format!("`{}` before `newmtl`", keyword)));
+        };
+        parameters.line(line, keyword, &mut tokens).map_err(error)?;
+    }
+
+    if let Some((name, parameters)) = current {
+        materials.insert(name, parameters.to_material(directory)?);
+    }
+
+    Ok(materials)
+}
+
+struct MtlParameters {
+    diffuse: Vec3,
+    specular: Vec3,
+    transmission: Vec3,
+    shininess: f32,
+    ior: f32,
+    dissolve: f32,
+    illum: u32,
+    diffuse_map: Option<PathBuf>,
+    normal_map: Option<PathBuf>,
+    // Height map and its `-bm` multiplier.
+    bump_map: Option<(PathBuf, f32)>,
+    // Values from the PBR extension (`Pr`, `Pm`, ...), which select a principled material.
+    roughness: Option<f32>,
+    metallic: Option<f32>,
+    sheen: Option<f32>,
+    clearcoat: Option<f32>,
+    clearcoat_roughness: Option<f32>,
+}
+
+impl Default for MtlParameters {
+    fn default() -> Self {
+        MtlParameters {
+            diffuse: Vec3::splat(0.8),
+            specular: Vec3::ZERO,
+            transmission: Vec3::ZERO,
+            shininess: 0.0,
+            ior: 1.5,
+            dissolve: 1.0,
+            illum: 2,
+            diffuse_map: None,
+            normal_map: None,
+            bump_map: None,
+            roughness: None,
+            metallic: None,
+            sheen: None,
+            clearcoat: None,
+            clearcoat_roughness: None,
+        }
+    }
+}
+
+impl MtlParameters {
+    fn line(
+        &mut self,
+        line: &str,
+        keyword: &str,
+        tokens: &mut SplitWhitespace,
+    ) -> Result<(), String> {
+        match keyword {
+            "Kd" => self.diffuse = Vec3::from(parse_floats::<3>(tokens)?),
+            "Ks" => self.specular = Vec3::from(parse_floats::<3>(tokens)?),
+            "Tf" => self.transmission = Vec3::from(parse_floats::<3>(tokens)?),
+            "Ns" => self.shininess = parse_float(tokens.next())?,
+            "Ni" => self.ior = parse_float(tokens.next())?,
+            "d" => self.dissolve = parse_float(tokens.next())?,
+            "Tr" => self.dissolve = 1.0 - parse_float(tokens.next())?,
+            // Texture options such as `-s` aren't supported; the file name comes last.
+            "map_Kd" => {
+                let file = rest(line, keyword)?.split_whitespace().last();
+                self.diffuse_map = file.map(PathBuf::from);
+            }
+            "norm" => {
+                let file = rest(line, keyword)?.split_whitespace().last();
+                self.normal_map = file.map(PathBuf::from);
+            }
+            "bump" | "map_Bump" | "map_bump" => {
+                let mut options = rest(line, keyword)?.split_whitespace();
+                let mut scale = 1.0;
+                let mut file = None;
+                while let Some(token) = options.next() {
+                    match token {
+                        "-bm" => scale = parse_float(options.next())?,
+                        token => file = Some(PathBuf::from(token)),
+                    }
+                }
+                self.bump_map = file.map(|file| (file, scale));
+            }
+            "Pr" => self.roughness = Some(parse_float(tokens.next())?),
+            "Pm" => self.metallic = Some(parse_float(tokens.next())?),
+            "Ps" => self.sheen = Some(parse_float(tokens.next())?),
+            "Pc" => self.clearcoat = Some(parse_float(tokens.next())?),
+            "Pcr" => self.clearcoat_roughness = Some(parse_float(tokens.next())?),
+            "illum" => {
+                self.illum = tokens
+                    .next()
+                    .ok_or("missing illumination model")?
+                    .parse()
+                    .map_err(|_| "invalid illumination model")?
+            }
+            // Parameters without a counterpart in the current materials.
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // Normal and bump maps perturb whichever material the other parameters select.
+    fn to_material(&self, directory: &Path) -> ObjResult<Arc<dyn Material>> {
+        let material = self.to_base_material(directory)?;
+        if let Some(file) = &self.normal_map {
+            let texture = load_texture(directory, file, true)?;
+            return Ok(Arc::new(NormalMapped::normal_map(material, texture)));
+        }
+        if let Some((file, scale)) = &self.bump_map {
+            let texture = load_texture(directory, file, true)?;
+            return Ok(Arc::new(NormalMapped::bump_map(material, texture, *scale)));
+        }
+        Ok(material)
+    }
+
+    // Textures only apply to diffuse materials, where they replace `Kd`.
+    fn to_base_material(&self, directory: &Path) -> ObjResult<Arc<dyn Material>> {
+        if let Some(material) = self.to_principled() {
+            return Ok(Arc::new(material));
+        }
+
+        // Exporters write `Tf 1 1 1` as a default even for opaque materials, so only a filter
+        // that actually tints makes a material transparent on its own.
+        let transparent = self.dissolve < 1.0
+            || matches!(self.illum, 4 | 6 | 7 | 9)
+            || (self.transmission.max_element() > 0.0 && self.transmission != Vec3::ONE);
+
+        if transparent {
+            let glass = Dielectric::new(self.ior);
+            if self.transmission.max_element() > 0.0 {
+                // `Tf` is the color passed through; take it as what is left after one unit.
+                let tint = Color::<Linear>::from(self.transmission);
+                return Ok(Arc::new(glass.with_tint(tint, 1.0)));
+            }
+            return Ok(Arc::new(glass));
+        }
+
+        let reflective =
+            self.illum == 3 || self.specular.max_element() > self.diffuse.max_element();
+        if reflective && self.specular.max_element() > 0.0 {
+            // Map the Phong exponent onto a microfacet alpha the way Blinn-Phong and Beckmann
+            // agree, then back to perceptual roughness.
+            let alpha = (2.0 / (self.shininess + 2.0)).sqrt();
+            return Ok(Arc::new(Microfacet::new(
+                Color::<Linear>::from(self.specular),
+                alpha.sqrt(),
+            )));
+        }
+
+        if let Some(file) = &self.diffuse_map {
+            let texture = load_texture(directory, file, false)?;
+            return Ok(Arc::new(Lambertian::from_texture(texture)));
+        }
+
+        Ok(Arc::new(Lambertian::new(Color::from(self.diffuse))))
+    }
+
+    fn to_principled(&self) -> Option<Principled> {
+        if self.roughness.is_none() && self.metallic.is_none() {
+            return None;
+        }
+
+        let mut material = Principled::new(Color::from(self.diffuse))
+            .with_roughness(self.roughness.unwrap_or(0.5))
+            .with_metallic(self.metallic.unwrap_or(0.0))
+            .with_sheen(self.sheen.unwrap_or(0.0))
+            .with_ior(self.ior)
+            .with_transmission(1.0 - self.dissolve);
+        if let Some(clearcoat) = self.clearcoat {
+            material = material.with_clearcoat(clearcoat, self.clearcoat_roughness.unwrap_or(0.03));
+        }
+        Some(material)
+    }
+}
+
+fn load_texture(directory: &Path, file: &Path, data: bool) -> ObjResult<Arc<dyn Texture>> {
+    let path = directory.join(file);
+    let texture = if data {
+        ImageTexture::load_data(&path)
+    } else {
+        ImageTexture::load(&path)
+    };
+    let texture = texture.map_err(|source| ObjError::Image { path, source })?;
+    Ok(Arc::new(texture))
+}
+
+fn strip_comment(line: &str) -> &str {
+    line.split_once('#')
+        .map_or(line, |(content, _)| content)
+        .trim()
+}
+
+fn rest<'a>(line: &'a str, keyword: &str) -> Result<&'a str, String> {
+    let rest = line[keyword.len()..].trim();
+    if rest.is_empty() {
+        Err(format!("`{}` needs an argument", keyword))
+    } else {
+        Ok(rest)
+    }
+}
+
+fn parse_float(token: Option<&str>) -> Result<f32, String> {
+    let token = token.ok_or("missing number")?;
+    f32::from_str(token).map_err(|_| format!("invalid number `{}`", token))
+}
+
+fn parse_floats<const N: usize>(tokens: &mut SplitWhitespace) -> Result<[f32; N], String> {
+    let mut values = [0.0; N];
+    for value in values.iter_mut() {
+        *value = parse_float(tokens.next())?;
+    }
+    Ok(values)
+}
+
+fn resolve_index(token: Option<&str>, len: usize) -> Result<Option<usize>, String> {
+    let Some(token) = token.filter(|token| !token.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid index `{}`", token))?;
+
+    let resolved = match index {
+        i if i > 0 => i - 1,
+        i if i < 0 => len as i64 + i,
+        _ => return Err("index 0 is not valid".to_string()),
+    };
+
+    if resolved < 0 || resolved >= len as i64 {
+        return Err(format!("index {} is out of range", index));
+    }
+
+    Ok(Some(resolved as usize))
+}
@@ -4,11 +4,33 @@ use crate::{point::Point3, vec::Vec3};
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
+    // Hero wavelength in nanometers, chosen by the first dispersive surface on the path.
+    pub wavelength: Option<f32>,
+    // Moment within the shutter interval the path is traced at, for moving objects.
+    pub time: f32,
 }
 
 impl Ray {
     pub fn new(origin: Point3, direction: Vec3) -> Ray {
-        Ray { origin, direction }
+        Ray {
+            origin,
+            direction,
+            wavelength: None,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_time(self, time: f32) -> Ray {
+        Ray { time, ..self }
+    }
+
+    // Continues this ray's path from a new vertex.
+    pub fn spawn(&self, origin: Point3, direction: Vec3) -> Ray {
+        Ray {
+            wavelength: self.wavelength,
+            time: self.time,
+            ..Ray::new(origin, direction)
+        }
     }
 
     pub fn at(&self, t: f32) -> Point3 {
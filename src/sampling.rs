@@ -0,0 +1,145 @@
+use std::f32::consts::{FRAC_1_PI, PI, TAU};
+
+use crate::vec::Vec2;
+
+// Veach's power heuristic with beta = 2, weighting a sample from `pdf` against `other_pdf`.
+// Written in terms of the ratio of the two so that squaring huge densities can't overflow.
+pub fn power_heuristic(pdf: f32, other_pdf: f32) -> f32 {
+    if pdf >= other_pdf && pdf > 0.0 {
+        let ratio = other_pdf / pdf;
+        1.0 / (1.0 + ratio * ratio)
+    } else if other_pdf > 0.0 {
+        let ratio = pdf / other_pdf;
+        ratio * ratio / (1.0 + ratio * ratio)
+    } else {
+        0.0
+    }
+}
+
+// Densities of the `VecExt` direction samplers, per unit solid angle.
+pub fn cosine_hemisphere_pdf(cos_theta: f32) -> f32 {
+    cos_theta.max(0.0) * FRAC_1_PI
+}
+
+pub fn uniform_hemisphere_pdf() -> f32 {
+    1.0 / TAU
+}
+
+pub fn uniform_cone_pdf(cos_theta_max: f32) -> f32 {
+    1.0 / (TAU * (1.0 - cos_theta_max))
+}
+
+pub fn uniform_sphere_pdf() -> f32 {
+    1.0 / (4.0 * PI)
+}
+
+// Piecewise-constant distribution over [0, 1) built from non-negative function values.
+#[derive(Clone, Debug)]
+pub struct Distribution1D {
+    func: Vec<f32>,
+    cdf: Vec<f32>,
+    integral: f32,
+}
+
+impl Distribution1D {
+    pub fn new(func: Vec<f32>) -> Self {
+        assert!(!func.is_empty(), "distribution needs at least one value");
+        let n = func.len();
+
+        let mut cdf = Vec::with_capacity(n + 1);
+        cdf.push(0.0);
+        for value in &func {
+            cdf.push(cdf.last().unwrap() + value.max(0.0) / n as f32);
+        }
+
+        let integral = cdf[n];
+        if integral > 0.0 {
+            cdf.iter_mut().for_each(|c| *c /= integral);
+        } else {
+            cdf.iter_mut()
+                .enumerate()
+                .for_each(|(i, c)| *c = i as f32 / n as f32);
+        }
+
+        Self {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.func.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.func.is_empty()
+    }
+
+    pub fn integral(&self) -> f32 {
+        self.integral
+    }
+
+    // Returns the sampled point, its density and the index of the segment it fell in.
+    pub fn sample(&self, u: f32) -> (f32, f32, usize) {
+        let index = (self.cdf.partition_point(|&c| c <= u) - 1).min(self.len() - 1);
+
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let du = if width > 0.0 {
+            (u - self.cdf[index]) / width
+        } else {
+            0.0
+        };
+
+        let x = ((index as f32 + du) / self.len() as f32).min(1.0 - f32::EPSILON);
+        (x, self.pdf_at(index), index)
+    }
+
+    pub fn pdf(&self, x: f32) -> f32 {
+        let index = ((x * self.len() as f32) as usize).min(self.len() - 1);
+        self.pdf_at(index)
+    }
+
+    fn pdf_at(&self, index: usize) -> f32 {
+        if self.integral > 0.0 {
+            self.func[index] / self.integral
+        } else {
+            1.0
+        }
+    }
+}
+
+// Distribution over the unit square, stored row by row as `height` rows of `width` values.
+#[derive(Clone, Debug)]
+pub struct Distribution2D {
+    conditional: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(func: &[f32], width: usize, height: usize) -> Self {
+        assert_eq!(func.len(), width * height, "distribution size mismatch");
+
+        let conditional: Vec<Distribution1D> = func
+            .chunks(width)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+        let marginal = Distribution1D::new(conditional.iter().map(|row| row.integral()).collect());
+
+        Self {
+            conditional,
+            marginal,
+        }
+    }
+
+    pub fn sample(&self, u: Vec2) -> (Vec2, f32) {
+        let (v, pdf_v, row) = self.marginal.sample(u.y);
+        let (u, pdf_u, _) = self.conditional[row].sample(u.x);
+        (Vec2::new(u, v), pdf_u * pdf_v)
+    }
+
+    pub fn pdf(&self, point: Vec2) -> f32 {
+        let row = ((point.y * self.marginal.len() as f32) as usize).min(self.marginal.len() - 1);
+        self.marginal.pdf(point.y) * self.conditional[row].pdf(point.x)
+    }
+}
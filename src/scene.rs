@@ -0,0 +1,922 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io, mem,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use glam::Affine3A;
+use image::{ImageError, ImageFormat, ImageResult};
+
+use crate::{
+    aabb::Aabb,
+    background::{
+        environment_map::EnvironmentMap, Background, Black, Gradient, PhysicalSky, Solid,
+    },
+    camera::{Camera, CameraConfig},
+    color::{Color, Linear},
+    hittable::{
+        bvh::Bvh,
+        cone::Cone,
+        constant_medium::{ConstantMedium, Fog},
+        cuboid::Cuboid,
+        cylinder::Cylinder,
+        disk::Disk,
+        plane::Plane,
+        quad::Quad,
+        sphere::Sphere,
+        torus::Torus,
+        transformed::{self, Transformed},
+        triangle::Triangle,
+        Hittable, HittableList, LightList,
+    },
+    integrator::{self, ambient_occlusion::AmbientOcclusion, Integrator},
+    material::{
+        dielectric::{Dielectric, Dispersion},
+        diffuse_light::DiffuseLight,
+        henyey_greenstein::HenyeyGreenstein,
+        isotropic::Isotropic,
+        lambertian::Lambertian,
+        metal::Metal,
+        microfacet::Microfacet,
+        normal_map::NormalMapped,
+        principled::Principled,
+        Material,
+    },
+    obj::{self, ObjError},
+    texture::{checker::Checker, image::ImageTexture, noise::Noise, solid::SolidColor, Texture},
+    vec::Vec3,
+};
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { line: usize, message: String },
+    Obj { line: usize, source: ObjError },
+    Image { line: usize, source: ImageError },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            SceneError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            SceneError::Obj { line, source } => write!(f, "line {}: {}", line, source),
+            SceneError::Image { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io { source, .. } => Some(source),
+            SceneError::Parse { .. } => None,
+            SceneError::Obj { source, .. } => Some(source),
+            SceneError::Image { source, .. } => Some(source),
+        }
+    }
+}
+
+pub type SceneResult<T> = Result<T, SceneError>;
+
+pub struct Scene {
+    pub camera: CameraConfig,
+    pub world: HittableList,
+    // Emitters that are also in `world`, sampled directly at every diffuse bounce.
+    pub lights: LightList,
+}
+
+impl Scene {
+    pub fn load(path: impl AsRef<Path>) -> SceneResult<Scene> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| SceneError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Scene::parse(&source, path.parent().unwrap_or(Path::new("")))
+    }
+
+    // Relative paths inside the scene, such as meshes, resolve against `base_directory`.
+    pub fn parse(source: &str, base_directory: &Path) -> SceneResult<Scene> {
+        let statements = parse_statements(&mut tokenize(source)?.into_iter(), None)?;
+
+        let mut builder = SceneBuilder {
+            base_directory,
+            camera: CameraConfig::default(),
+            frame_camera: false,
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+            transform: Keyframes::IDENTITY,
+            objects: HashMap::new(),
+            world: HittableList::new(),
+            lights: LightList::new(),
+        };
+        for statement in &statements {
+            builder.statement(statement)?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    pub fn render(self, path: impl Into<PathBuf>) -> ImageResult<()> {
+        let path: PathBuf = path.into();
+        let format = crate::output_format(&path)?;
+        self.render_with_format(path, format)
+    }
+
+    pub fn render_with_format(
+        self,
+        path: impl Into<PathBuf>,
+        format: ImageFormat,
+    ) -> ImageResult<()> {
+        let camera = Camera::new(&self.camera);
+        let image = camera.render(&Bvh::from(self.world), &self.lights);
+        crate::save(image, path.into(), format)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    OpenBrace,
+    CloseBrace,
+    Newline,
+}
+
+fn tokenize(source: &str) -> SceneResult<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let mut chars = line.char_indices().peekable();
+
+        while let Some(&(start, c)) = chars.peek() {
+            match c {
+                '#' => break,
+                c if c.is_whitespace() => {
+                    chars.next();
+                }
+                '{' => {
+                    chars.next();
+                    tokens.push((number, Token::OpenBrace));
+                }
+                '}' => {
+                    chars.next();
+                    tokens.push((number, Token::CloseBrace));
+                }
+                '"' => {
+                    chars.next();
+                    let mut value = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, c)) => value.push(c),
+                            None => return Err(parse_error(number, "unterminated string")),
+                        }
+                    }
+                    tokens.push((number, Token::Str(value)));
+                }
+                _ => {
+                    let mut end = line.len();
+                    while let Some(&(i, c)) = chars.peek() {
+                        if c.is_whitespace() || matches!(c, '{' | '}' | '"' | '#') {
+                            end = i;
+                            break;
+                        }
+                        chars.next();
+                    }
+                    tokens.push((number, Token::Word(line[start..end].to_string())));
+                }
+            }
+        }
+
+        tokens.push((number, Token::Newline));
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug)]
+struct Statement {
+    line: usize,
+    keyword: String,
+    args: Vec<Token>,
+    block: Option<Vec<Statement>>,
+}
+
+// `block_line` is the line that opened the enclosing block, if any.
+fn parse_statements(
+    tokens: &mut impl Iterator<Item = (usize, Token)>,
+    block_line: Option<usize>,
+) -> SceneResult<Vec<Statement>> {
+    let in_block = block_line.is_some();
+    let mut statements = Vec::new();
+
+    while let Some((line, token)) = tokens.next() {
+        let keyword = match token {
+            Token::Newline => continue,
+            Token::CloseBrace if in_block => return Ok(statements),
+            Token::CloseBrace => return Err(parse_error(line, "unexpected `}`")),
+            Token::OpenBrace => return Err(parse_error(line, "unexpected `{`")),
+            Token::Str(_) => return Err(parse_error(line, "expected a statement, found a string")),
+            Token::Word(word) => word,
+        };
+
+        let mut args = Vec::new();
+        let mut block = None;
+        while let Some((_, token)) = tokens.next() {
+            match token {
+                Token::Newline => break,
+                Token::OpenBrace => {
+                    block = Some(parse_statements(tokens, Some(line))?);
+                    break;
+                }
+                Token::CloseBrace if in_block => {
+                    statements.push(Statement {
+                        line,
+                        keyword,
+                        args,
+                        block,
+                    });
+                    return Ok(statements);
+                }
+                Token::CloseBrace => return Err(parse_error(line, "unexpected `}`")),
+                token => args.push(token),
+            }
+        }
+
+        statements.push(Statement {
+            line,
+            keyword,
+            args,
+            block,
+        });
+    }
+
+    if let Some(line) = block_line {
+        return Err(parse_error(line, "missing closing `}`"));
+    }
+
+    Ok(statements)
+}
+
+fn parse_error(line: usize, message: impl Into<String>) -> SceneError {
+    SceneError::Parse {
+        line,
+        message: message.into(),
+    }
+}
+
+struct Args<'a> {
+    statement: &'a Statement,
+    position: usize,
+}
+
+impl<'a> Args<'a> {
+    fn new(statement: &'a Statement) -> Args<'a> {
+        Args {
+            statement,
+            position: 0,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> SceneError {
+        parse_error(self.statement.line, message)
+    }
+
+    fn next(&mut self, expected: &str) -> SceneResult<&'a Token> {
+        let token = self.statement.args.get(self.position).ok_or_else(|| {
+            self.error(format!(
+                "`{}` is missing {}",
+                self.statement.keyword, expected
+            ))
+        })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn word(&mut self, expected: &str) -> SceneResult<&'a str> {
+        match self.next(expected)? {
+            Token::Word(word) => Ok(word),
+            _ => Err(self.error(format!("expected {}", expected))),
+        }
+    }
+
+    fn string(&mut self, expected: &str) -> SceneResult<&'a str> {
+        match self.next(expected)? {
+            Token::Str(value) | Token::Word(value) => Ok(value),
+            _ => Err(self.error(format!("expected {}", expected))),
+        }
+    }
+
+    fn float(&mut self) -> SceneResult<f32> {
+        let word = self.word("a number")?;
+        parse_float(word).ok_or_else(|| self.error(format!("invalid number `{}`", word)))
+    }
+
+    fn uint(&mut self) -> SceneResult<u32> {
+        let word = self.word("an integer")?;
+        word.parse()
+            .map_err(|_| self.error(format!("invalid integer `{}`", word)))
+    }
+
+    fn vec3(&mut self) -> SceneResult<Vec3> {
+        Ok(Vec3::new(self.float()?, self.float()?, self.float()?))
+    }
+
+    fn color(&mut self) -> SceneResult<Color<Linear>> {
+        Ok(Color::from(self.vec3()?))
+    }
+
+    fn next_is_number(&self) -> bool {
+        matches!(
+            self.statement.args.get(self.position),
+            Some(Token::Word(word)) if parse_float(word).is_some()
+        )
+    }
+
+    fn next_is(&self, word: &str) -> bool {
+        matches!(self.statement.args.get(self.position), Some(Token::Word(next)) if next == word)
+    }
+
+    fn has_more(&self) -> bool {
+        self.position < self.statement.args.len()
+    }
+
+    fn end(&self) -> SceneResult<()> {
+        if self.has_more() {
+            Err(self.error(format!(
+                "too many arguments for `{}`",
+                self.statement.keyword
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Accepts plain numbers and ratios such as `16/9`.
+fn parse_float(word: &str) -> Option<f32> {
+    match word.split_once('/') {
+        Some((numerator, denominator)) => {
+            Some(numerator.parse::<f32>().ok()? / denominator.parse::<f32>().ok()?)
+        }
+        None => word.parse().ok(),
+    }
+}
+
+// Transform at time 0 and 1, which differ for moving objects.
+#[derive(Clone, Copy, PartialEq)]
+struct Keyframes {
+    start: Affine3A,
+    end: Affine3A,
+}
+
+impl Keyframes {
+    const IDENTITY: Keyframes = Keyframes {
+        start: Affine3A::IDENTITY,
+        end: Affine3A::IDENTITY,
+    };
+
+    // `local` applied first, then this placement.
+    fn then(self, local: Keyframes) -> Keyframes {
+        Keyframes {
+            start: self.start * local.start,
+            end: self.end * local.end,
+        }
+    }
+
+    // Moving transforms are interpolated part by part, which shear would break.
+    fn check(self, args: &Args) -> SceneResult<Keyframes> {
+        if self.start != self.end
+            && !(transformed::is_decomposable(&self.start)
+                && transformed::is_decomposable(&self.end))
+        {
+            return Err(args.error(
+                "a moving transform must not shear; scale along the object's own axes before \
+                 rotating it",
+            ));
+        }
+        Ok(self)
+    }
+
+    fn apply(self, object: Arc<dyn Hittable>) -> Arc<dyn Hittable> {
+        if self == Keyframes::IDENTITY {
+            object
+        } else if self.start == self.end {
+            Arc::new(Transformed::new(object, self.start))
+        } else {
+            Arc::new(Transformed::moving(object, self.start, self.end))
+        }
+    }
+}
+
+struct SceneBuilder<'a> {
+    base_directory: &'a Path,
+    camera: CameraConfig,
+    frame_camera: bool,
+    textures: HashMap<String, Arc<dyn Texture>>,
+    materials: HashMap<String, (Arc<dyn Material>, bool)>,
+    // Applied to every object added, set by enclosing `transform` blocks.
+    transform: Keyframes,
+    // Named groups for `instance`, with the emitters among their members.
+    objects: HashMap<String, (Arc<dyn Hittable>, LightList)>,
+    world: HittableList,
+    lights: LightList,
+}
+
+impl SceneBuilder<'_> {
+    fn statement(&mut self, statement: &Statement) -> SceneResult<()> {
+        let takes_block = matches!(
+            statement.keyword.as_str(),
+            "camera" | "transform" | "object"
+        );
+        if statement.block.is_some() && !takes_block {
+            return Err(parse_error(
+                statement.line,
+                format!("`{}` does not take a block", statement.keyword),
+            ));
+        }
+
+        let mut args = Args::new(statement);
+        match statement.keyword.as_str() {
+            "camera" => {
+                args.end()?;
+                let Some(block) = &statement.block else {
+                    return Err(args.error("`camera` needs a block"));
+                };
+                for field in block {
+                    self.camera_field(field)?;
+                }
+                return Ok(());
+            }
+            "transform" => {
+                let local = self.transform_ops(&mut args)?;
+                let Some(block) = &statement.block else {
+                    return Err(args.error("`transform` needs a block"));
+                };
+                let outer = self.transform;
+                self.transform = outer.then(local).check(&args)?;
+                for statement in block {
+                    self.statement(statement)?;
+                }
+                self.transform = outer;
+            }
+            "object" => {
+                let name = args.word("an object name")?;
+                args.end()?;
+                let Some(block) = &statement.block else {
+                    return Err(args.error("`object` needs a block"));
+                };
+                // Members are collected on their own, in the object's local space.
+                let outer = mem::replace(&mut self.transform, Keyframes::IDENTITY);
+                let world = mem::take(&mut self.world);
+                let lights = mem::take(&mut self.lights);
+                for statement in block {
+                    self.statement(statement)?;
+                }
+                let members = mem::replace(&mut self.world, world);
+                let emitters = mem::replace(&mut self.lights, lights);
+                self.transform = outer;
+
+                let object: Arc<dyn Hittable> = Arc::new(Bvh::from(members));
+                self.objects.insert(name.to_string(), (object, emitters));
+                return Ok(());
+            }
+            "instance" => {
+                let name = args.word("an object name")?;
+                let (object, emitters) = self
+                    .objects
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| args.error(format!("undefined object `{}`", name)))?;
+                let local = self.transform_ops(&mut args)?;
+                let transform = self.transform.then(local).check(&args)?;
+                self.world.push(Box::new(transform.apply(object)));
+                for emitter in emitters {
+                    self.lights.push(transform.apply(emitter));
+                }
+            }
+            "medium" => {
+                let name = args.word("an object name")?;
+                let (boundary, _) = self
+                    .objects
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| args.error(format!("undefined object `{}`", name)))?;
+                let density = args.float()?;
+                let (phase, _) = self.material_ref(&mut args)?;
+                let medium = ConstantMedium::new(boundary, density, phase);
+                self.push(Arc::new(medium), false);
+            }
+            "fog" => {
+                let density = args.float()?;
+                let (phase, _) = self.material_ref(&mut args)?;
+                let mut fog = Fog::new(density, phase);
+                if args.has_more() {
+                    fog = fog.with_max_distance(args.float()?);
+                }
+                self.camera.fog = Some(fog);
+            }
+            "background" => self.camera.background = self.background(&mut args)?,
+            "integrator" => self.camera.integrator = self.integrator(&mut args)?,
+            "texture" => {
+                let name = args.word("a texture name")?;
+                let texture = self.texture(&mut args)?;
+                self.textures.insert(name.to_string(), texture);
+            }
+            "material" => {
+                let name = args.word("a material name")?;
+                let material = self.material(&mut args)?;
+                self.materials.insert(name.to_string(), material);
+            }
+            "sphere" => {
+                let center = args.vec3()?;
+                let radius = args.float()?;
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(Arc::new(Sphere::new(center, radius, material)), emissive);
+            }
+            "moving_sphere" => {
+                let (start, end) = (args.vec3()?, args.vec3()?);
+                let radius = args.float()?;
+                let (material, emissive) = self.material_ref(&mut args)?;
+                let sphere = Sphere::moving(start, end, radius, material);
+                self.push(Arc::new(sphere), emissive);
+            }
+            "triangle" => {
+                let (a, b, c) = (args.vec3()?, args.vec3()?, args.vec3()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(Arc::new(Triangle::new(a, b, c, material)), emissive);
+            }
+            "plane" => {
+                let (point, normal) = (args.vec3()?, args.vec3()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(Arc::new(Plane::new(point, normal, material)), emissive);
+            }
+            "quad" => {
+                let (q, u, v) = (args.vec3()?, args.vec3()?, args.vec3()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(Arc::new(Quad::new(q, u, v, material)), emissive);
+            }
+            "disk" => {
+                let (center, normal, radius) = (args.vec3()?, args.vec3()?, args.float()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(
+                    Arc::new(Disk::new(center, normal, radius, material)),
+                    emissive,
+                );
+            }
+            "box" => {
+                let (a, b) = (args.vec3()?, args.vec3()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(Arc::new(Cuboid::new(a, b, material)), emissive);
+            }
+            "cylinder" => {
+                let (base, top, radius) = (args.vec3()?, args.vec3()?, args.float()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(
+                    Arc::new(Cylinder::new(base, top, radius, material)),
+                    emissive,
+                );
+            }
+            "cone" => {
+                let (base, apex, radius) = (args.vec3()?, args.vec3()?, args.float()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                self.push(Arc::new(Cone::new(base, apex, radius, material)), emissive);
+            }
+            "torus" => {
+                let (center, axis) = (args.vec3()?, args.vec3()?);
+                let (major_radius, minor_radius) = (args.float()?, args.float()?);
+                let (material, emissive) = self.material_ref(&mut args)?;
+                let torus = Torus::new(center, axis, major_radius, minor_radius, material);
+                self.push(Arc::new(torus), emissive);
+            }
+            "mesh" => {
+                let path = self.base_directory.join(args.string("a file path")?);
+                let material = if args.has_more() {
+                    Some(self.material_ref(&mut args)?)
+                } else {
+                    None
+                };
+                let meshes = match &material {
+                    Some((material, _)) => obj::load_with_material(&path, material.clone()),
+                    None => obj::load(&path),
+                }
+                .map_err(|source| SceneError::Obj {
+                    line: statement.line,
+                    source,
+                })?;
+                let emissive = material.is_some_and(|(_, emissive)| emissive);
+                for mesh in meshes {
+                    self.push(Arc::new(mesh), emissive);
+                }
+            }
+            keyword => return Err(args.error(format!("unknown statement `{}`", keyword))),
+        }
+
+        args.end()
+    }
+
+    fn camera_field(&mut self, field: &Statement) -> SceneResult<()> {
+        let mut args = Args::new(field);
+        let camera = &mut self.camera;
+        match field.keyword.as_str() {
+            "aspect_ratio" => camera.aspect_ratio = args.float()?,
+            "image_width" => camera.image_width = args.uint()?,
+            "samples_per_pixel" => camera.samples_per_pixel = args.uint()?,
+            "max_depth" => camera.max_depth = args.uint()?,
+            "vfov" => camera.vfov = args.float()?,
+            "look_from" => camera.look_from = args.vec3()?,
+            "look_at" => camera.look_at = args.vec3()?,
+            "vup" => camera.vup = args.vec3()?,
+            "defocus_angle" => camera.defocus_angle = args.float()?,
+            "focus_distance" => camera.focus_distance = args.float()?,
+            "shutter" => {
+                camera.shutter_open = args.float()?;
+                camera.shutter_close = args.float()?;
+            }
+            "frame" => self.frame_camera = true,
+            keyword => return Err(args.error(format!("unknown camera field `{}`", keyword))),
+        }
+        args.end()
+    }
+
+    fn background(&self, args: &mut Args) -> SceneResult<Arc<dyn Background>> {
+        let background: Arc<dyn Background> = match args.word("a background type")? {
+            "black" => Arc::new(Black),
+            "solid" => Arc::new(Solid::new(args.color()?)),
+            "gradient" => Arc::new(Gradient::new(args.color()?, args.color()?)),
+            "sky" => {
+                let sky = PhysicalSky::new(args.vec3()?, args.float()?);
+                if args.has_more() {
+                    Arc::new(sky.with_intensity(args.float()?))
+                } else {
+                    Arc::new(sky)
+                }
+            }
+            "environment" => {
+                let path = self.base_directory.join(args.string("an image path")?);
+                let mut map = EnvironmentMap::load(&path).map_err(|source| SceneError::Image {
+                    line: args.statement.line,
+                    source,
+                })?;
+                if args.has_more() {
+                    map = map.with_intensity(args.float()?);
+                }
+                if args.has_more() {
+                    map = map.with_rotation(args.float()?);
+                }
+                Arc::new(map)
+            }
+            kind => return Err(args.error(format!("unknown background type `{}`", kind))),
+        };
+        Ok(background)
+    }
+
+    fn integrator(&self, args: &mut Args) -> SceneResult<Arc<dyn Integrator>> {
+        match args.word("an integrator name")? {
+            "ao" | "ambient_occlusion" if args.has_more() => {
+                Ok(Arc::new(AmbientOcclusion::new(args.float()?)))
+            }
+            name => integrator::by_name(name)
+                .ok_or_else(|| args.error(format!("unknown integrator `{}`", name))),
+        }
+    }
+
+    fn texture(&self, args: &mut Args) -> SceneResult<Arc<dyn Texture>> {
+        let texture: Arc<dyn Texture> = match args.word("a texture type")? {
+            "solid" => Arc::new(SolidColor::new(args.color()?)),
+            "checker" => Arc::new(Checker::new(
+                args.float()?,
+                self.color_texture(args)?,
+                self.color_texture(args)?,
+            )),
+            "image" => {
+                let path = self.base_directory.join(args.string("an image path")?);
+                let data = match args.has_more() {
+                    true => match args.word("`data`")? {
+                        "data" => true,
+                        word => return Err(args.error(format!("expected `data`, got `{}`", word))),
+                    },
+                    false => false,
+                };
+                let texture = if data {
+                    ImageTexture::load_data(&path)
+                } else {
+                    ImageTexture::load(&path)
+                };
+                Arc::new(texture.map_err(|source| SceneError::Image {
+                    line: args.statement.line,
+                    source,
+                })?)
+            }
+            "noise" => {
+                let noise = Noise::new(args.float()?);
+                if args.has_more() {
+                    Arc::new(noise.with_colors(args.color()?, args.color()?))
+                } else {
+                    Arc::new(noise)
+                }
+            }
+            kind => return Err(args.error(format!("unknown texture type `{}`", kind))),
+        };
+        Ok(texture)
+    }
+
+    // A constant color or the name of a texture.
+    fn color_texture(&self, args: &mut Args) -> SceneResult<Arc<dyn Texture>> {
+        if args.next_is_number() {
+            return Ok(Arc::new(SolidColor::new(args.color()?)));
+        }
+        self.texture_ref(args)
+    }
+
+    // A constant number or the name of a texture.
+    fn scalar_texture(&self, args: &mut Args) -> SceneResult<Arc<dyn Texture>> {
+        if args.next_is_number() {
+            return Ok(Arc::new(SolidColor::gray(args.float()?)));
+        }
+        self.texture_ref(args)
+    }
+
+    fn texture_ref(&self, args: &mut Args) -> SceneResult<Arc<dyn Texture>> {
+        let name = args.word("a color or texture name")?;
+        self.textures
+            .get(name)
+            .cloned()
+            .ok_or_else(|| args.error(format!("undefined texture `{}`", name)))
+    }
+
+    // Returns the material and whether it emits light.
+    fn material(&self, args: &mut Args) -> SceneResult<(Arc<dyn Material>, bool)> {
+        let kind = args.word("a material type")?;
+        let material: Arc<dyn Material> = match kind {
+            "lambertian" => Arc::new(Lambertian::from_texture(self.color_texture(args)?)),
+            "metal" => Arc::new(Metal::from_texture(
+                self.color_texture(args)?,
+                self.scalar_texture(args)?,
+            )),
+            "microfacet" => Arc::new(Microfacet::new(args.color()?, args.float()?)),
+            "conductor" => Arc::new(Microfacet::conductor(
+                args.color()?,
+                args.color()?,
+                args.float()?,
+            )),
+            "dielectric" => Arc::new(self.dielectric(args)?),
+            "principled" => Arc::new(self.principled(args)?),
+            "diffuse_light" => Arc::new(DiffuseLight::new(args.color()?)),
+            "isotropic" => Arc::new(Isotropic::from_texture(self.color_texture(args)?)),
+            "henyey_greenstein" => Arc::new(HenyeyGreenstein::from_texture(
+                self.color_texture(args)?,
+                args.float()?,
+            )),
+            "normal_map" => {
+                let (base, emissive) = self.material_ref(args)?;
+                let texture = self.texture_ref(args)?;
+                return Ok((Arc::new(NormalMapped::normal_map(base, texture)), emissive));
+            }
+            "bump_map" => {
+                let (base, emissive) = self.material_ref(args)?;
+                let height = self.scalar_texture(args)?;
+                let scale = args.float()?;
+                return Ok((
+                    Arc::new(NormalMapped::bump_map(base, height, scale)),
+                    emissive,
+                ));
+            }
+            kind => return Err(args.error(format!("unknown material type `{}`", kind))),
+        };
+        Ok((material, kind == "diffuse_light"))
+    }
+
+    // Index of refraction followed by any number of `parameter value` pairs.
+    fn dielectric(&self, args: &mut Args) -> SceneResult<Dielectric> {
+        let mut material = Dielectric::new(args.float()?);
+        while args.has_more() {
+            material = match args.word("a dielectric parameter")? {
+                "absorption" => material.with_absorption(args.color()?),
+                "tint" => material.with_tint(args.color()?, args.float()?),
+                "roughness" => material.with_roughness(args.float()?),
+                "cauchy" => material.with_dispersion(Dispersion::Cauchy {
+                    a: args.float()?,
+                    b: args.float()?,
+                }),
+                "sellmeier" => material.with_dispersion(Dispersion::Sellmeier {
+                    b: [args.float()?, args.float()?, args.float()?],
+                    c: [args.float()?, args.float()?, args.float()?],
+                }),
+                "bk7" => material.with_dispersion(Dispersion::BK7),
+                "diamond" => material.with_dispersion(Dispersion::DIAMOND),
+                name => return Err(args.error(format!("unknown dielectric parameter `{}`", name))),
+            };
+        }
+        Ok(material)
+    }
+
+    // Base color followed by any number of `parameter value` pairs.
+    fn principled(&self, args: &mut Args) -> SceneResult<Principled> {
+        let mut material = Principled::new(args.color()?);
+        while args.has_more() {
+            material = match args.word("a principled parameter")? {
+                "metallic" => material.with_metallic(args.float()?),
+                "roughness" => material.with_roughness(args.float()?),
+                "specular" => material.with_specular(args.float()?),
+                "clearcoat" => material.with_clearcoat(args.float()?, args.float()?),
+                "sheen" => material.with_sheen(args.float()?),
+                "transmission" => material.with_transmission(args.float()?),
+                "ior" => material.with_ior(args.float()?),
+                name => return Err(args.error(format!("unknown principled parameter `{}`", name))),
+            };
+        }
+        Ok(material)
+    }
+
+    fn material_ref(&self, args: &mut Args) -> SceneResult<(Arc<dyn Material>, bool)> {
+        let name = args.word("a material name")?;
+        self.materials
+            .get(name)
+            .cloned()
+            .ok_or_else(|| args.error(format!("undefined material `{}`", name)))
+    }
+
+    // Transform operations for time 0, optionally followed by `to` and those for time 1.
+    fn transform_ops(&self, args: &mut Args) -> SceneResult<Keyframes> {
+        let start = self.affine_ops(args)?;
+        let end = if args.has_more() {
+            args.word("`to`")?;
+            self.affine_ops(args)?
+        } else {
+            start
+        };
+        Ok(Keyframes { start, end })
+    }
+
+    // A sequence of `translate x y z`, `scale s` or `scale x y z`, `rotate x y z degrees` and
+    // `rotate_x|rotate_y|rotate_z degrees`, applied to the object in the order given and
+    // ending at `to` or the end of the statement.
+    fn affine_ops(&self, args: &mut Args) -> SceneResult<Affine3A> {
+        let mut transform = Affine3A::IDENTITY;
+        while args.has_more() && !args.next_is("to") {
+            let op = match args.word("a transform")? {
+                "translate" => Affine3A::from_translation(args.vec3()?.into()),
+                "scale" => {
+                    let x = args.float()?;
+                    if args.next_is_number() {
+                        Affine3A::from_scale(glam::Vec3::new(x, args.float()?, args.float()?))
+                    } else {
+                        Affine3A::from_scale(glam::Vec3::splat(x))
+                    }
+                }
+                "rotate" => {
+                    let axis = args.vec3()?;
+                    let angle = args.float()?.to_radians();
+                    let Some(axis) = axis.try_normalize() else {
+                        return Err(args.error("rotation axis must not be zero"));
+                    };
+                    Affine3A::from_axis_angle(axis.into(), angle)
+                }
+                "rotate_x" => Affine3A::from_rotation_x(args.float()?.to_radians()),
+                "rotate_y" => Affine3A::from_rotation_y(args.float()?.to_radians()),
+                "rotate_z" => Affine3A::from_rotation_z(args.float()?.to_radians()),
+                name => return Err(args.error(format!("unknown transform `{}`", name))),
+            };
+            transform = op * transform;
+        }
+
+        if transform.matrix3.determinant() == 0.0 {
+            return Err(args.error("transform is not invertible"));
+        }
+        Ok(transform)
+    }
+
+    fn push(&mut self, object: Arc<dyn Hittable>, emissive: bool) {
+        let object = self.transform.apply(object);
+        if emissive {
+            self.lights.push(object.clone());
+        }
+        self.world.push(Box::new(object));
+    }
+
+    fn finish(self) -> Scene {
+        let camera = if self.frame_camera {
+            // Frame what can be framed, leaving out infinite planes and the like.
+            let bbox = self
+                .world
+                .iter()
+                .map(|object| object.bounding_box())
+                .filter(|bbox| !bbox.is_unbounded())
+                .fold(Aabb::EMPTY, |acc, bbox| acc.union(&bbox));
+            self.camera.framing(&bbox)
+        } else {
+            self.camera
+        };
+
+        Scene {
+            camera,
+            world: self.world,
+            lights: self.lights,
+        }
+    }
+}
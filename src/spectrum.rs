@@ -0,0 +1,54 @@
+use std::{ops::Range, sync::LazyLock};
+
+use crate::{utils::Random, vec::Vec3};
+
+// Visible wavelengths in nanometers that rays sample their hero wavelength from.
+pub const WAVELENGTHS: Range<f32> = 380.0..720.0;
+
+// Average of the clamped RGB response over `WAVELENGTHS`, so white light stays white.
+static RESPONSE_SCALE: LazyLock<Vec3> = LazyLock::new(|| {
+    const STEPS: usize = 1000;
+    let width = WAVELENGTHS.end - WAVELENGTHS.start;
+    let sum = (0..STEPS)
+        .map(|i| raw_response(WAVELENGTHS.start + width * (i as f32 + 0.5) / STEPS as f32))
+        .fold(Vec3::ZERO, |sum, rgb| sum + rgb);
+    STEPS as f32 / sum
+});
+
+pub fn sample_wavelength() -> f32 {
+    f32::random_range(&WAVELENGTHS)
+}
+
+// Linear RGB weight of a uniformly sampled wavelength, normalized to average to one.
+pub fn wavelength_response(wavelength: f32) -> Vec3 {
+    raw_response(wavelength) * *RESPONSE_SCALE
+}
+
+fn raw_response(wavelength: f32) -> Vec3 {
+    let xyz = cie_xyz(wavelength);
+    let rgb = Vec3::new(
+        3.2406 * xyz.x - 1.5372 * xyz.y - 0.4986 * xyz.z,
+        -0.9689 * xyz.x + 1.8758 * xyz.y + 0.0415 * xyz.z,
+        0.0557 * xyz.x - 0.2040 * xyz.y + 1.0570 * xyz.z,
+    );
+    rgb.max(Vec3::ZERO)
+}
+
+// Multi-lobe Gaussian fit of the CIE 1931 color matching functions (Wyman et al. 2013).
+fn cie_xyz(wavelength: f32) -> Vec3 {
+    let g = |mu: f32, sigma_below: f32, sigma_above: f32| {
+        let sigma = if wavelength < mu {
+            sigma_below
+        } else {
+            sigma_above
+        };
+        let t = (wavelength - mu) / sigma;
+        (-0.5 * t * t).exp()
+    };
+
+    Vec3::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
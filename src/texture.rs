@@ -0,0 +1,19 @@
+pub mod checker;
+pub mod image;
+pub mod noise;
+pub mod solid;
+
+use crate::{
+    color::{Color, Linear},
+    point::Point3,
+    vec::Vec2,
+};
+
+pub trait Texture: Send + Sync + std::fmt::Debug {
+    fn value(&self, uv: Vec2, point: Point3) -> Color<Linear>;
+
+    // Single-channel parameters such as roughness read the average of the channels.
+    fn scalar(&self, uv: Vec2, point: Point3) -> f32 {
+        self.value(uv, point).v.element_sum() / 3.0
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use crate::{
+    color::{Color, Linear},
+    point::Point3,
+    vec::Vec2,
+};
+
+use super::Texture;
+
+// Alternates between two textures in cubes of side `scale`, filling space rather than
+// following the surface parameterization.
+#[derive(Clone, Debug)]
+pub struct Checker {
+    inv_scale: f32,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn new(scale: f32, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, uv: Vec2, point: Point3) -> Color<Linear> {
+        let cell = (point * self.inv_scale).floor();
+        if (cell.x + cell.y + cell.z).rem_euclid(2.0) < 1.0 {
+            self.even.value(uv, point)
+        } else {
+            self.odd.value(uv, point)
+        }
+    }
+}
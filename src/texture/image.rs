@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use image::{DynamicImage, ImageResult};
+
+use crate::{
+    color::{Color, Linear, Srgb},
+    point::Point3,
+    vec::{Vec2, Vec3},
+};
+
+use super::Texture;
+
+// Image looked up by UV with (0, 0) at the bottom-left corner, repeating outside [0, 1].
+#[derive(Clone, Debug)]
+pub struct ImageTexture {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vec3>,
+}
+
+impl ImageTexture {
+    pub fn load(path: impl AsRef<Path>) -> ImageResult<Self> {
+        Ok(Self::new(image::open(path)?))
+    }
+
+    pub fn load_data(path: impl AsRef<Path>) -> ImageResult<Self> {
+        Ok(Self::new_data(image::open(path)?))
+    }
+
+    // 8 and 16 bit images are taken to be sRGB encoded, floating point ones linear.
+    pub fn new(image: DynamicImage) -> Self {
+        let linear = matches!(
+            image,
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+        );
+        Self::decode(image, linear)
+    }
+
+    // For non-color data such as normal and height maps, which is never sRGB encoded.
+    pub fn new_data(image: DynamicImage) -> Self {
+        Self::decode(image, true)
+    }
+
+    fn decode(image: DynamicImage, linear: bool) -> Self {
+        let image = image.into_rgb32f();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels = image
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0;
+                if linear {
+                    Vec3::new(r, g, b)
+                } else {
+                    Color::<Srgb>::new(r, g, b).to_linear().v
+                }
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, uv: Vec2, _point: Point3) -> Color<Linear> {
+        let u = uv.x.rem_euclid(1.0);
+        let v = 1.0 - uv.y.rem_euclid(1.0);
+        let x = ((u * self.width as f32) as usize).min(self.width - 1);
+        let y = ((v * self.height as f32) as usize).min(self.height - 1);
+        Color::from(self.pixels[y * self.width + x])
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{
+    color::{Color, Linear},
+    point::Point3,
+    utils::Random,
+    vec::{Vec2, Vec3, VecExt},
+};
+
+use super::Texture;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise on a lattice of random unit vectors (Perlin 1985, with Hermite smoothing).
+#[derive(Clone, Debug)]
+pub struct Perlin {
+    gradients: Vec<Vec3>,
+    permutations: [Vec<usize>; 3],
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        Self {
+            gradients: (0..POINT_COUNT).map(|_| Vec3::random_unit()).collect(),
+            permutations: [permutation(), permutation(), permutation()],
+        }
+    }
+
+    // Smooth noise in roughly [-1, 1].
+    pub fn noise(&self, point: Point3) -> f32 {
+        let cell = point.floor();
+        let f = point - cell;
+        let smooth = f * f * (Vec3::splat(3.0) - 2.0 * f);
+        let (i, j, k) = (cell.x as i32, cell.y as i32, cell.z as i32);
+
+        let mut sum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let index = self.permutations[0][((i + di) & 255) as usize]
+                        ^ self.permutations[1][((j + dj) & 255) as usize]
+                        ^ self.permutations[2][((k + dk) & 255) as usize];
+                    let corner = Vec3::new(di as f32, dj as f32, dk as f32);
+                    let weight = corner * smooth + (Vec3::ONE - corner) * (Vec3::ONE - smooth);
+                    sum += weight.x * weight.y * weight.z * self.gradients[index].dot(f - corner);
+                }
+            }
+        }
+        sum
+    }
+
+    // Sum of `depth` octaves of absolute noise, each twice the frequency and half the weight.
+    pub fn turbulence(&self, point: Point3, depth: u32) -> f32 {
+        let mut sum = 0.0;
+        let mut point = point;
+        let mut weight = 1.0;
+        for _ in 0..depth {
+            sum += weight * self.noise(point);
+            weight *= 0.5;
+            point *= 2.0;
+        }
+        sum.abs()
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn permutation() -> Vec<usize> {
+    let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+    for i in (1..POINT_COUNT).rev() {
+        let target = ((f32::random() * (i + 1) as f32) as usize).min(i);
+        p.swap(i, target);
+    }
+    p
+}
+
+// Marble-like veins between `low` and `high`, with features about `1 / scale` across.
+#[derive(Clone, Debug)]
+pub struct Noise {
+    perlin: Perlin,
+    scale: f32,
+    low: Color<Linear>,
+    high: Color<Linear>,
+}
+
+impl Noise {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            perlin: Perlin::new(),
+            scale,
+            low: Color::from(Vec3::ZERO),
+            high: Color::from(Vec3::ONE),
+        }
+    }
+
+    pub fn with_colors(self, low: Color<Linear>, high: Color<Linear>) -> Self {
+        Self { low, high, ..self }
+    }
+}
+
+impl Texture for Noise {
+    fn value(&self, _uv: Vec2, point: Point3) -> Color<Linear> {
+        let phase = self.scale * point.z + 10.0 * self.perlin.turbulence(point, 7);
+        let t = 0.5 * (1.0 + phase.sin());
+        Color::from(self.low.v.lerp(self.high.v, t))
+    }
+}
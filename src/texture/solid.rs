@@ -0,0 +1,34 @@
+use crate::{
+    color::{Color, Linear},
+    point::Point3,
+    vec::{Vec2, Vec3},
+};
+
+use super::Texture;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SolidColor {
+    color: Color<Linear>,
+}
+
+impl SolidColor {
+    pub fn new(color: Color<Linear>) -> Self {
+        Self { color }
+    }
+
+    pub fn gray(value: f32) -> Self {
+        Self::new(Color::from(Vec3::splat(value)))
+    }
+}
+
+impl From<Color<Linear>> for SolidColor {
+    fn from(color: Color<Linear>) -> Self {
+        Self::new(color)
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _uv: Vec2, _point: Point3) -> Color<Linear> {
+        self.color
+    }
+}
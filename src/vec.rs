@@ -1,7 +1,8 @@
-use std::ops::Range;
+use std::{f32::consts::TAU, ops::Range};
 
 use crate::utils::Random;
 
+pub type Vec2 = glam::Vec2;
 pub type Vec3 = glam::Vec3A;
 
 impl Random<f32> for Vec3 {
@@ -18,18 +19,54 @@ impl Random<f32> for Vec3 {
     }
 }
 
-pub(crate) trait VecExt {
-    fn near_zero(&self) -> bool;
+// Right-handed orthonormal basis whose `w` axis is a given direction.
+#[derive(Clone, Copy, Debug)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    pub fn new(w: Vec3) -> Self {
+        let w = w.normalize();
+        let (u, v) = w.any_orthonormal_pair();
+        Self { u, v, w }
+    }
+
+    // Keeps `u` as close to `tangent` as possible, falling back to an arbitrary frame when the
+    // tangent is parallel to `w`.
+    pub fn from_tangent(w: Vec3, tangent: Vec3) -> Self {
+        let w = w.normalize();
+        match (tangent - w * w.dot(tangent)).try_normalize() {
+            Some(u) => Self {
+                u,
+                v: w.cross(u),
+                w,
+            },
+            None => Self::new(w),
+        }
+    }
+
+    pub fn to_world(&self, local: Vec3) -> Vec3 {
+        local.x * self.u + local.y * self.v + local.z * self.w
+    }
+
+    pub fn to_local(&self, world: Vec3) -> Vec3 {
+        Vec3::new(world.dot(self.u), world.dot(self.v), world.dot(self.w))
+    }
+}
+
+// Direction samplers return unit vectors in a local frame around +Z; map them with `Onb`.
+pub trait VecExt {
     fn random_unit() -> Vec3;
     fn random_in_unit_disk() -> Vec3;
+    fn random_cosine_direction() -> Vec3;
+    fn random_on_hemisphere() -> Vec3;
+    fn random_in_cone(cos_theta_max: f32) -> Vec3;
 }
 
 impl VecExt for Vec3 {
-    fn near_zero(&self) -> bool {
-        const S: f32 = 1e-8;
-        self.x.abs() < S && self.y.abs() < S && self.z.abs() < S
-    }
-
     fn random_unit() -> Vec3 {
         loop {
             let p = Vec3::random_range(&(-1.0..1.0));
@@ -52,4 +89,22 @@ impl VecExt for Vec3 {
             }
         }
     }
+
+    fn random_cosine_direction() -> Vec3 {
+        let (r1, r2) = (f32::random(), f32::random());
+        let phi = TAU * r1;
+        let r = r2.sqrt();
+        Vec3::new(phi.cos() * r, phi.sin() * r, (1.0 - r2).sqrt())
+    }
+
+    fn random_on_hemisphere() -> Vec3 {
+        Vec3::random_in_cone(0.0)
+    }
+
+    fn random_in_cone(cos_theta_max: f32) -> Vec3 {
+        let z = 1.0 + f32::random() * (cos_theta_max - 1.0);
+        let phi = TAU * f32::random();
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+        Vec3::new(phi.cos() * sin_theta, phi.sin() * sin_theta, z)
+    }
 }